@@ -0,0 +1,119 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Strategies for combining the cases of the parameterised fixtures of a test.
+//!
+//! A strategy receives the number of cases of each fixture injected into a test
+//! and returns the combinations to execute, each given by the index of the case used for every fixture.
+//! A strategy is selected with the `#[combine(...)]` attribute of a test.
+//!
+//! The strategies are re-exported by `galvanic-test` as `galvanic_test::combinations`
+//! and used by `galvanic-test-macros` to name the tests generated by `#[per_case]` after the executed combinations.
+
+use std::collections::BTreeSet;
+
+/// A strategy for combining the cases of the fixtures of a test.
+pub type Combinator = fn(&[usize]) -> Vec<Vec<usize>>;
+
+/// All combinations of the fixtures' cases, i.e., their cartesian product.
+///
+/// The cases of the first fixture vary slowest. This is the default strategy.
+pub fn product(sizes: &[usize]) -> Vec<Vec<usize>> {
+    let mut combinations = vec![Vec::new()];
+    for &size in sizes {
+        combinations = combinations
+            .into_iter()
+            .flat_map(|combination| {
+                (0..size).map(move |index| {
+                    let mut combination = combination.clone();
+                    combination.push(index);
+                    combination
+                })
+            })
+            .collect();
+    }
+    combinations
+}
+
+/// Combines the n-th cases of all fixtures, stopping at the end of the fixture with the fewest cases.
+pub fn zip(sizes: &[usize]) -> Vec<Vec<usize>> {
+    let shortest = sizes.iter().cloned().min().unwrap_or(1);
+    (0..shortest).map(|index| vec![index; sizes.len()]).collect()
+}
+
+/// Combinations covering every pair of cases of any two fixtures at least once.
+///
+/// The number of combinations grows with the product of the two largest fixtures
+/// instead of the product of all fixtures.
+/// The combinations are chosen greedily and are the same for every execution of the test.
+pub fn pairwise(sizes: &[usize]) -> Vec<Vec<usize>> {
+    if sizes.len() <= 2 || sizes.contains(&0) {
+        return product(sizes);
+    }
+
+    // pairs of (fixture, case) which are not covered yet
+    let mut uncovered = BTreeSet::new();
+    for first in 0..sizes.len() {
+        for second in first + 1..sizes.len() {
+            for first_case in 0..sizes[first] {
+                for second_case in 0..sizes[second] {
+                    uncovered.insert(((first, first_case), (second, second_case)));
+                }
+            }
+        }
+    }
+
+    let mut combinations = Vec::new();
+    while let Some(&((first, first_case), (second, second_case))) = uncovered.iter().next() {
+        let mut combination: Vec<Option<usize>> = vec![None; sizes.len()];
+        combination[first] = Some(first_case);
+        combination[second] = Some(second_case);
+
+        for fixture in 0..sizes.len() {
+            if combination[fixture].is_some() {
+                continue;
+            }
+            // the case covering most of the uncovered pairs with the fixtures chosen so far
+            let newly_covered = |case: usize| {
+                combination
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(other, &other_case)| other_case.map(|other_case| (other, other_case)))
+                    .filter(|&other| {
+                        let pair = if other.0 < fixture {
+                            (other, (fixture, case))
+                        } else {
+                            ((fixture, case), other)
+                        };
+                        uncovered.contains(&pair)
+                    })
+                    .count()
+            };
+            let best = (0..sizes[fixture])
+                .max_by_key(|&case| (newly_covered(case), std::cmp::Reverse(case)))
+                .unwrap_or(0);
+            combination[fixture] = Some(best);
+        }
+
+        let combination: Vec<usize> = combination.into_iter().map(|case| case.unwrap_or(0)).collect();
+        for first in 0..sizes.len() {
+            for second in first + 1..sizes.len() {
+                uncovered.remove(&((first, combination[first]), (second, combination[second])));
+            }
+        }
+        combinations.push(combination);
+    }
+    combinations
+}
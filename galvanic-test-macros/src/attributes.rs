@@ -0,0 +1,210 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Expansion of the attributes declaring fixtures and tests as plain functions.
+//!
+//! The functions are rewritten to `fixture!` and `test!` invocations
+//! so that fixtures and tests declared by attributes and by the macros can be used together.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Attribute, FnArg, Ident, ItemFn, Pat, ReturnType, Signature, Token, Type};
+
+/// A typed argument `name: Type` of a function declaring a fixture or a test.
+struct Arg {
+    attrs: Vec<Attribute>,
+    mutability: Option<Token![mut]>,
+    name: Ident,
+    ty: Type,
+}
+
+/// Returns the arguments of a function without generics, receiver or patterns other than identifiers.
+fn plain_args(sig: &Signature, kind: &str) -> syn::Result<Vec<Arg>> {
+    if !sig.generics.params.is_empty() || sig.generics.where_clause.is_some() {
+        return Err(syn::Error::new_spanned(&sig.generics, format!("{} functions must not be generic", kind)));
+    }
+    if let Some(ref variadic) = sig.variadic {
+        return Err(syn::Error::new_spanned(variadic, format!("{} functions must not be variadic", kind)));
+    }
+    sig.inputs
+        .iter()
+        .map(|input| match *input {
+            FnArg::Typed(ref arg) => match *arg.pat {
+                Pat::Ident(ref pat) if pat.by_ref.is_none() && pat.subpat.is_none() => Ok(Arg {
+                    attrs: arg.attrs.clone(),
+                    mutability: pat.mutability,
+                    name: pat.ident.clone(),
+                    ty: (*arg.ty).clone(),
+                }),
+                ref pat => Err(syn::Error::new_spanned(
+                    pat,
+                    format!("the arguments of {} functions must be plain identifiers", kind),
+                )),
+            },
+            FnArg::Receiver(ref receiver) => Err(syn::Error::new_spanned(
+                receiver,
+                format!("{} functions must not take `self`", kind),
+            )),
+        })
+        .collect()
+}
+
+/// Rejects attributes on the arguments of a fixture or tear down function.
+fn without_arg_attrs(args: &[Arg]) -> syn::Result<()> {
+    match args.iter().flat_map(|arg| &arg.attrs).next() {
+        Some(attr) => Err(syn::Error::new_spanned(attr, "attributes on arguments are not supported here")),
+        None => Ok(()),
+    }
+}
+
+/// Expands `#[fixture(...)] fn name(params...) -> T { ... }` to a `fixture!` with the function's body as `setup`.
+///
+/// The arguments of the attribute are put in front of the `setup` block, e.g., a `params` block.
+pub fn fixture(entries: TokenStream, item: ItemFn) -> syn::Result<TokenStream> {
+    let args = plain_args(&item.sig, "fixture")?;
+    without_arg_attrs(&args)?;
+    // the fixture is a struct generated by `fixture!` which cannot carry the function's attributes
+    if let Some(attr) = item.attrs.iter().find(|attr| !attr.path().is_ident("doc")) {
+        return Err(syn::Error::new_spanned(attr, "fixture functions only support doc comments as attributes"));
+    }
+
+    let ItemFn { sig, block, .. } = item;
+    let name = &sig.ident;
+    let ret_ty = match sig.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ref ty) => quote!(#ty),
+    };
+    let params = args.iter().map(|arg| {
+        let (name, ty) = (&arg.name, &arg.ty);
+        quote!(#name: #ty)
+    });
+    // the function receives its own copy of the parameters of the current case
+    let copies: Vec<TokenStream> = args
+        .iter()
+        .map(|arg| {
+            let (mutability, name, ty) = (&arg.mutability, &arg.name, &arg.ty);
+            quote!(let #mutability #name: #ty = ::std::clone::Clone::clone(self.#name);)
+        })
+        .collect();
+    // the body is wrapped so that a `return` leaves the function's body only
+    let setup = match sig.asyncness {
+        Some(_) => quote! {
+            async setup(&mut self) {
+                #(#copies)*
+                async move #block.await
+            }
+        },
+        None => quote! {
+            setup(&mut self) {
+                #(#copies)*
+                (move || -> #ret_ty #block)()
+            }
+        },
+    };
+
+    Ok(quote! {
+        ::galvanic_test::fixture!( #name(#(#params),*) -> #ret_ty {
+            #entries
+            #setup
+            tear_down(&self) {
+                // replaced by the method of a `#[teardown]` function for this fixture
+                #[allow(unused_imports)]
+                use ::galvanic_test::NoTearDown as _;
+                self.__galvanic_tear_down();
+            }
+        });
+    })
+}
+
+/// Expands `#[teardown(fixture)] fn name(params...) { ... }` to the tear down of a fixture declared by `#[fixture]`.
+///
+/// The function receives the parameters of the fixture it names by reference.
+pub fn teardown(fixture: TokenStream, item: ItemFn) -> syn::Result<TokenStream> {
+    let fixture: Ident = syn::parse2(fixture).map_err(|error| {
+        syn::Error::new(error.span(), "expected the name of the torn down fixture, e.g., `#[teardown(input_file)]`")
+    })?;
+    let args = plain_args(&item.sig, "tear down")?;
+    without_arg_attrs(&args)?;
+
+    let function = &item.sig.ident;
+    let params = args.iter().map(|arg| &arg.name);
+    let call = quote!(#function(#(self.#params),*));
+    let call = match item.sig.asyncness {
+        Some(_) => quote!(::galvanic_test::block_on(#call)),
+        None => call,
+    };
+
+    Ok(quote! {
+        #item
+
+        // an inherent method takes precedence over the method of `NoTearDown` called by the fixture's `tear_down`
+        impl #fixture<'_> {
+            #[doc(hidden)]
+            pub fn __galvanic_tear_down(&self) {
+                #call;
+            }
+        }
+    })
+}
+
+/// Expands `#[test] fn name(fixtures...) { ... }` to a `test!` injecting the fixtures named by the arguments.
+///
+/// An argument's type, e.g., `FixtureBinding<input_file, File>`, is checked against the injected fixture.
+/// Arguments for a fixture's parameters are given by a `#[with(...)]` attribute on the argument.
+pub fn test(args: TokenStream, item: ItemFn) -> syn::Result<TokenStream> {
+    if !args.is_empty() {
+        return Err(syn::Error::new(
+            args.span(),
+            "`#[test]` takes no arguments, galvanic-specific attributes like `#[parallel]` are put on the function",
+        ));
+    }
+    let fixtures = plain_args(&item.sig, "test")?;
+
+    let mut uses = Vec::new();
+    for fixture in &fixtures {
+        let name = &fixture.name;
+        match fixture.attrs.as_slice() {
+            [] => uses.push(quote!(#name)),
+            [attr] if attr.path().is_ident("with") => {
+                let args = attr.meta.require_list()?.tokens.clone();
+                uses.push(quote!(#name(#args)));
+            }
+            [attr, ..] => {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "fixtures only support a single `#[with(...)]` attribute giving their parameters",
+                ))
+            }
+        }
+    }
+    let bindings = fixtures.iter().map(|fixture| {
+        let (mutability, name, ty) = (&fixture.mutability, &fixture.name, &fixture.ty);
+        quote!(let #mutability #name: #ty = #name;)
+    });
+
+    let ItemFn { attrs, sig, block, .. } = item;
+    let (asyncness, name, output) = (&sig.asyncness, &sig.ident, &sig.output);
+    let stmts = &block.stmts;
+    let body = quote!({
+        #(#bindings)*
+        #(#stmts)*
+    });
+    Ok(if fixtures.is_empty() {
+        quote!(::galvanic_test::test!( #(#attrs)* #asyncness #name #output #body );)
+    } else {
+        quote!(::galvanic_test::test!( #(#attrs)* #asyncness #name | #(#uses),* | #output #body );)
+    })
+}
@@ -0,0 +1,75 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The compile time check rejecting cyclic dependencies between fixtures.
+//!
+//! Each fixture has a `const fn` walking its dependencies along the path of fixtures depending on them.
+//! The function is evaluated for each fixture and panics if the fixture is reached again, which fails the compilation.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{bracketed, Ident, Token};
+
+/// The input of `__acyclic_dependencies!`, the name of a fixture and its dependencies in brackets.
+pub struct AcyclicDependencies {
+    fixture: Ident,
+    dependencies: Punctuated<Ident, Token![,]>,
+}
+
+impl Parse for AcyclicDependencies {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let fixture = input.parse()?;
+        let dependencies;
+        bracketed!(dependencies in input);
+        Ok(AcyclicDependencies {
+            fixture,
+            dependencies: Punctuated::parse_terminated(&dependencies)?,
+        })
+    }
+}
+
+impl AcyclicDependencies {
+    pub fn expand(self) -> TokenStream {
+        let fixture = &self.fixture;
+        let check = check_ident();
+        let dependencies = self.dependencies.iter();
+        let message = format!("The fixture `{}` depends on itself.", fixture);
+        // the errors of a cycle point at the fixtures instead of their whole declarations
+        quote_spanned! {fixture.span()=>
+            impl #fixture<'_> {
+                #[doc(hidden)]
+                pub const fn #check(dependent: Option<&::galvanic_test::DependencyPath<'_>>) {
+                    let path = ::galvanic_test::DependencyPath {
+                        fixture: concat!(module_path!(), "::", stringify!(#fixture)),
+                        dependent,
+                    };
+                    if path.is_cyclic() {
+                        panic!("{}", #message);
+                    }
+                    #(#dependencies::#check(Some(&path));)*
+                }
+            }
+
+            const _: () = #fixture::#check(None);
+        }
+    }
+}
+
+/// The name of the `const fn` checking the dependencies of a fixture.
+pub fn check_ident() -> Ident {
+    format_ident!("__galvanic_check_dependencies")
+}
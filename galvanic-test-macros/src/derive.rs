@@ -0,0 +1,226 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Expansion of `#[derive(TestFixture)]` for user-defined fixture structs.
+//!
+//! The fields of the struct are either references to the parameters of the fixture's case, marked by `#[fixture(param)]`,
+//! or members initialised by `Default`, marked by `#[fixture(member)]`.
+//! The functions setting up and tearing down the fixture are methods of the struct named by `#[fixture(...)]` on the struct.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Data, DeriveInput, Fields, GenericParam, Ident, LitStr, Type};
+
+use crate::dependencies;
+
+/// The settings given by `#[fixture(...)]` on the struct.
+#[derive(Default)]
+struct Settings {
+    setup: Option<Ident>,
+    tear_down: Option<Ident>,
+    params: Option<Ident>,
+    value: Option<Type>,
+}
+
+impl Settings {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut settings = Settings::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("fixture")) {
+            attr.parse_nested_meta(|meta| {
+                let value: LitStr = meta.value()?.parse()?;
+                if meta.path.is_ident("setup") {
+                    settings.setup = Some(value.parse()?);
+                } else if meta.path.is_ident("tear_down") {
+                    settings.tear_down = Some(value.parse()?);
+                } else if meta.path.is_ident("params") {
+                    settings.params = Some(value.parse()?);
+                } else if meta.path.is_ident("value") {
+                    settings.value = Some(value.parse()?);
+                } else {
+                    return Err(meta.error("expected `setup`, `tear_down`, `params` or `value`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(settings)
+    }
+}
+
+/// The role of a field of the fixture struct given by `#[fixture(...)]` on the field.
+enum Field {
+    /// A reference to one of the parameters of the fixture's case, the type is the referenced parameter's type.
+    Param(Ident, Box<Type>),
+    Member(Ident),
+}
+
+impl Field {
+    fn parse(field: &syn::Field) -> syn::Result<Self> {
+        let name = field.ident.clone().expect("the fields of the fixture struct are named");
+        let mut role = None;
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("fixture")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("param") {
+                    role = Some(true);
+                } else if meta.path.is_ident("member") {
+                    role = Some(false);
+                } else {
+                    return Err(meta.error("expected `param` or `member`"));
+                }
+                Ok(())
+            })?;
+        }
+        match (role, &field.ty) {
+            (Some(true), Type::Reference(reference)) if reference.mutability.is_none() => {
+                Ok(Field::Param(name, reference.elem.clone()))
+            }
+            (Some(true), ty) => Err(syn::Error::new_spanned(
+                ty,
+                "fixture parameters must be declared as references `&'param T` to the parameters of the fixture's case",
+            )),
+            (Some(false), _) => Ok(Field::Member(name)),
+            (None, _) => Err(syn::Error::new_spanned(
+                &name,
+                "the fields of a fixture must be marked as `#[fixture(param)]` or `#[fixture(member)]`",
+            )),
+        }
+    }
+}
+
+pub fn derive_test_fixture(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => fields.named.iter().map(Field::parse).collect::<syn::Result<Vec<_>>>()?,
+            // the binding of an injected fixture is named after the fixture and cannot shadow a unit or tuple struct
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "a fixture struct must have named fields, e.g., `struct Fixture {}` instead of `struct Fixture;`",
+                ))
+            }
+        },
+        _ => return Err(syn::Error::new_spanned(name, "only structs can be derived as `TestFixture`")),
+    };
+    let settings = Settings::parse(&input.attrs)?;
+
+    // the parameters of the fixture's case are borrowed for the lifetime of the struct
+    let mut lifetime = None;
+    for param in &input.generics.params {
+        match *param {
+            GenericParam::Lifetime(ref param) if lifetime.is_none() => lifetime = Some(param.lifetime.clone()),
+            ref param => {
+                return Err(syn::Error::new_spanned(
+                    param,
+                    "a fixture struct may only be generic over the lifetime of its parameters",
+                ))
+            }
+        }
+    }
+    let (impl_lifetime, struct_lifetime) = match lifetime {
+        Some(ref lifetime) => (quote!(#lifetime), quote!(<#lifetime>)),
+        None => (quote!('param), quote!()),
+    };
+
+    let setup = match settings.setup {
+        Some(ref setup) => setup,
+        None => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "a fixture requires a method setting it up, e.g., `#[fixture(setup = \"connect\")]`",
+            ))
+        }
+    };
+    let value = match settings.value {
+        Some(ref value) => quote!(#value),
+        None => quote!(()),
+    };
+
+    let params: Vec<(&Ident, &Type)> = fields
+        .iter()
+        .filter_map(|field| match *field {
+            Field::Param(ref name, ref ty) => Some((name, &**ty)),
+            Field::Member(_) => None,
+        })
+        .collect();
+    let members = fields.iter().filter_map(|field| match *field {
+        Field::Member(ref name) => Some(name),
+        Field::Param(..) => None,
+    });
+    let param_names: Vec<&Ident> = params.iter().map(|&(name, _)| name).collect();
+    let param_types: Vec<&Type> = params.iter().map(|&(_, ty)| ty).collect();
+    let (params_ty, bind_params) = match params.len() {
+        0 => (quote!(()), quote!()),
+        1 => (quote!(#(#param_types)*), quote!(let #(#param_names)* = curried_params;)),
+        _ => (
+            quote!((#(#param_types),*)),
+            quote!(let &(#(ref #param_names),*) = curried_params;),
+        ),
+    };
+
+    let parameters = match settings.params {
+        Some(ref params) => quote!(Some(Box::new(::std::iter::IntoIterator::into_iter(Self::#params())))),
+        None if params.is_empty() => quote!(Some(Box::new(Some(()).into_iter()))),
+        None => quote!(None),
+    };
+    let tear_down = settings.tear_down.as_ref().map(|tear_down| {
+        quote! {
+            fn tear_down(&self) {
+                self.#tear_down();
+            }
+        }
+    });
+    let check = dependencies::check_ident();
+
+    Ok(quote! {
+        impl<#impl_lifetime> ::galvanic_test::TestFixture<#impl_lifetime, #params_ty, #value> for #name #struct_lifetime {
+            #[allow(unused_variables)]
+            fn new(curried_params: &#impl_lifetime #params_ty) -> Self {
+                #bind_params
+                #name {
+                    #(#param_names,)*
+                    #(#members: ::std::default::Default::default(),)*
+                }
+            }
+
+            fn parameters() -> Option<Box<dyn Iterator<Item = #params_ty>>> {
+                #parameters
+            }
+
+            fn setup(&mut self) -> ::galvanic_test::FixtureBinding<'_, Self, #value> {
+                let value = self.#setup();
+                ::galvanic_test::FixtureBinding { val: value, params: self }
+            }
+
+            #tear_down
+        }
+
+        impl #struct_lifetime ::std::ops::Drop for #name #struct_lifetime {
+            fn drop(&mut self) {
+                <Self as ::galvanic_test::TestFixture<'_, #params_ty, #value>>::tear_down(self);
+            }
+        }
+
+        impl #struct_lifetime ::galvanic_test::FixtureValue for #name #struct_lifetime {
+            type Value = #value;
+        }
+
+        // a derived fixture has no dependencies, see `__acyclic_dependencies!`
+        impl #struct_lifetime #name #struct_lifetime {
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            pub const fn #check(_dependent: Option<&::galvanic_test::DependencyPath<'_>>) {}
+        }
+    })
+}
@@ -0,0 +1,51 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Compile errors pointing at the offending tokens of a malformed fixture or test.
+
+use proc_macro2::TokenStream;
+use syn::parse::{Parse, ParseStream};
+use syn::{bracketed, LitStr};
+
+/// The input of `__dsl_error!` as generated by the diagnostic arms of the macros.
+pub struct DslError {
+    offending: TokenStream,
+    message: LitStr,
+}
+
+impl Parse for DslError {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let offending;
+        bracketed!(offending in input);
+        Ok(DslError {
+            offending: offending.parse()?,
+            message: input.parse()?,
+        })
+    }
+}
+
+impl DslError {
+    pub fn into_compile_error(self) -> TokenStream {
+        // `{}` in the message stands for the offending tokens
+        let message = self.message.value().replace("{}", &self.offending.to_string());
+        // tokens without a span of their own, e.g., the end of the input, are reported at the message's span
+        let error = if self.offending.is_empty() {
+            syn::Error::new(self.message.span(), message)
+        } else {
+            syn::Error::new_spanned(self.offending, message)
+        };
+        error.to_compile_error()
+    }
+}
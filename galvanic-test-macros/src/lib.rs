@@ -0,0 +1,140 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Procedural macros used by `galvanic-test`.
+//!
+//! The macros are re-exported by `galvanic-test` and should not be used from this crate directly.
+
+extern crate proc_macro;
+
+mod attributes;
+// the strategies of `galvanic_test::combinations` so that `#[per_case]` tests match the combinations of regular tests
+use galvanic_test_combinations as combinations;
+mod dependencies;
+mod derive;
+mod dsl_error;
+mod per_case;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, ItemFn};
+
+/// Generates a separate test function for each statically known parameterisation of a test.
+///
+/// Used by `test_suite!` for tests marked with `#[per_case]`.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __per_case_tests(input: TokenStream) -> TokenStream {
+    let tests = parse_macro_input!(input as per_case::PerCaseTests);
+    tests.expand().unwrap_or_else(|error| error.to_compile_error()).into()
+}
+
+/// Reports a malformed use of the macros of `galvanic-test`.
+///
+/// The input is the offending tokens in brackets followed by the message, e.g., `[teardown] "unexpected `{}`"`,
+/// where `{}` in the message is replaced by the offending tokens.
+/// Other than `compile_error!` the error points at the offending tokens instead of the whole macro invocation.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __dsl_error(input: TokenStream) -> TokenStream {
+    let error = parse_macro_input!(input as dsl_error::DslError);
+    error.into_compile_error().into()
+}
+
+/// Rejects cyclic dependencies between fixtures at compile time.
+///
+/// The input is the name of a fixture followed by its dependencies in brackets, e.g., `doubled [base]`.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __acyclic_dependencies(input: TokenStream) -> TokenStream {
+    let dependencies = parse_macro_input!(input as dependencies::AcyclicDependencies);
+    dependencies.expand().into()
+}
+
+/// Declares a fixture by a function whose body sets up the fixture.
+///
+/// The arguments of the function are the fixture's parameters and the function receives a copy of them for each case.
+/// The arguments of the attribute are put in front of the fixture's `setup` like in `fixture!`, e.g., a `params` block.
+///
+/// ```rust,ignore
+/// #[galvanic::fixture(params { vec![String::from("a.txt")].into_iter() })]
+/// fn input_file(name: String) -> File {
+///     File::open(name).unwrap()
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn fixture(args: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as ItemFn);
+    attributes::fixture(args.into(), item).unwrap_or_else(|error| error.to_compile_error()).into()
+}
+
+/// Declares the tear down of the fixture declared by `#[fixture]` named by the attribute.
+///
+/// The arguments of the function are some of the fixture's parameters, passed by reference.
+///
+/// ```rust,ignore
+/// #[galvanic::teardown(input_file)]
+/// fn remove_input_file(name: &String) {
+///     fs::remove_file(name).unwrap();
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn teardown(args: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as ItemFn);
+    attributes::teardown(args.into(), item).unwrap_or_else(|error| error.to_compile_error()).into()
+}
+
+/// Declares a test whose arguments are the injected fixtures.
+///
+/// Each argument is named after a fixture and its type is the fixture's binding.
+/// The parameters of a fixture are given by a `#[with(...)]` attribute on its argument.
+/// The other attributes of the function are handled like those of `test!`, e.g., `#[parallel]`.
+///
+/// ```rust,ignore
+/// #[galvanic::test]
+/// fn read_input(input_file: FixtureBinding<input_file, File>, #[with(4)] buffer: FixtureBinding<buffer, Vec<u8>>) {
+///     // ...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn test(args: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as ItemFn);
+    attributes::test(args.into(), item).unwrap_or_else(|error| error.to_compile_error()).into()
+}
+
+/// Implements `TestFixture` for a struct whose methods set up and tear down the fixture.
+///
+/// The fields of the struct are marked as references to the fixture's parameters by `#[fixture(param)]`
+/// or as members initialised by `Default` by `#[fixture(member)]`.
+/// The struct is marked by `#[fixture(...)]` with the following settings, each naming a method or a type:
+/// * `setup`, the method `fn(&mut self) -> Value` setting up the fixture, which is required,
+/// * `tear_down`, the method `fn(&self)` tearing down the fixture,
+/// * `params`, the associated function returning the parameters of the fixture's cases as an `IntoIterator`,
+/// * `value`, the type of the value set up by the fixture, defaulting to `()`.
+///
+/// ```rust,ignore
+/// #[derive(Debug, TestFixture)]
+/// #[fixture(setup = "connect", tear_down = "disconnect", params = "urls", value = "Connection")]
+/// struct Database<'param> {
+///     #[fixture(param)]
+///     url: &'param String,
+///     #[fixture(member)]
+///     session: Option<Session>,
+/// }
+/// ```
+#[proc_macro_derive(TestFixture, attributes(fixture))]
+pub fn derive_test_fixture(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive::derive_test_fixture(input).unwrap_or_else(|error| error.to_compile_error()).into()
+}
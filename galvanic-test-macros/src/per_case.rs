@@ -0,0 +1,437 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Expansion of `#[per_case]` tests into one test function per parameterisation.
+//!
+//! The parameterisations of a fixture are statically known if its `params` block
+//! lists labelled `case`s or is a `vec![...]` or an array literal listing the parameter tuples.
+//! All other fixtures are injected as usual and iterate over their parameterisations within each test function.
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::quote;
+use syn::parse::{Parse, ParseStream, Parser};
+use syn::punctuated::Punctuated;
+use syn::{braced, bracketed, parenthesized, Attribute, Expr, Ident, Path, Token, Type};
+
+use crate::combinations;
+
+mod kw {
+    syn::custom_keyword!(fixtures);
+    syn::custom_keyword!(prelude);
+    syn::custom_keyword!(test);
+    syn::custom_keyword!(case);
+}
+
+/// A fixture declared in the test suite.
+struct FixtureDef {
+    name: Ident,
+    params: Vec<Ident>,
+    body: TokenStream,
+}
+
+impl Parse for FixtureDef {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+
+        let args;
+        parenthesized!(args in input);
+        let mut params = Vec::new();
+        while !args.is_empty() {
+            let arg: Ident = args.parse()?;
+            // arguments without a type are dependencies on other fixtures
+            if args.peek(Token![:]) {
+                args.parse::<Token![:]>()?;
+                args.parse::<Type>()?;
+                params.push(arg);
+            }
+            if !args.is_empty() {
+                args.parse::<Token![,]>()?;
+            }
+        }
+
+        let body;
+        braced!(body in input);
+        Ok(FixtureDef {
+            name,
+            params,
+            body: body.parse()?,
+        })
+    }
+}
+
+impl FixtureDef {
+    /// Returns the name suffixes of the test functions for each parameterisation if they are statically known.
+    fn static_cases(&self) -> Option<Vec<String>> {
+        let (labelled, params_block) = params_block(self.body.clone())?;
+        if !labelled {
+            let entries = Punctuated::<CaseEntry, Token![,]>::parse_terminated.parse2(params_block.clone());
+            if let Ok(entries) = entries {
+                return Some(entries.iter().map(|entry| format!("_{}", describe(&entry.label))).collect());
+            }
+        }
+
+        let (cases, in_order) = listed_cases(syn::parse2(params_block).ok()?)?;
+        // the cases may be reordered by the iterator, so they are only named by their index
+        if !in_order {
+            return Some(vec![String::new(); cases.len()]);
+        }
+        cases
+            .into_iter()
+            .map(|case| match case {
+                Expr::Tuple(ref tuple) if labelled && tuple.elems.len() == 2 => Some(format!("_{}", describe(&tuple.elems[0]))),
+                _ if labelled => None,
+                case => self.describe_params(case),
+            })
+            .collect()
+    }
+
+    /// Describes the parameters of an unlabelled case by their names and values.
+    fn describe_params(&self, case: Expr) -> Option<String> {
+        let args = match (self.params.len(), case) {
+            (1, case) => vec![case],
+            (n, Expr::Tuple(tuple)) if tuple.elems.len() == n => tuple.elems.into_iter().collect(),
+            _ => return None,
+        };
+        Some(
+            self.params
+                .iter()
+                .zip(&args)
+                .map(|(param, arg)| format!("_{}_{}", param, describe(arg)))
+                .collect(),
+        )
+    }
+}
+
+/// A labelled case `case "label" => params` of a `params` block.
+struct CaseEntry {
+    label: Expr,
+}
+
+impl Parse for CaseEntry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // the expected outcome of the case does not affect the name of its test
+        Attribute::parse_outer(input)?;
+        input.parse::<kw::case>()?;
+        let label = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        input.parse::<Expr>()?;
+        Ok(CaseEntry { label })
+    }
+}
+
+/// Returns the elements of a `vec![...]` or an array listing the cases of a fixture
+/// and whether the cases are iterated in the listed order.
+fn listed_cases(mut cases: Expr) -> Option<(Vec<Expr>, bool)> {
+    // `.into_iter()`, `.iter().cloned()` and the like do not change the cases, others like `.rev()` reorder them
+    let mut in_order = true;
+    while let Expr::MethodCall(call) = cases {
+        if !call.args.is_empty() {
+            return None;
+        }
+        in_order &= ["into_iter", "iter", "cloned", "copied", "to_vec"]
+            .iter()
+            .any(|method| call.method == method);
+        cases = *call.receiver;
+    }
+
+    let cases = match cases {
+        Expr::Array(array) => Some(array.elems.into_iter().collect()),
+        Expr::Macro(mac) if mac.mac.path.is_ident("vec") => Some(
+            mac.mac
+                .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+                .ok()?
+                .into_iter()
+                .collect(),
+        ),
+        _ => None,
+    };
+    cases.map(|cases| (cases, in_order))
+}
+
+/// Finds the contents of the `params { ... }` block in the body of a fixture
+/// and whether it is a `params labelled { ... }` block.
+fn params_block(body: TokenStream) -> Option<(bool, TokenStream)> {
+    let mut tokens = body.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        if let TokenTree::Ident(ref ident) = token {
+            if ident == "params" {
+                let labelled = matches!(tokens.peek(), Some(TokenTree::Ident(ident)) if ident == "labelled");
+                if labelled {
+                    tokens.next();
+                }
+                if let Some(TokenTree::Group(group)) = tokens.peek() {
+                    return Some((labelled, group.stream()));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// A fixture injected into the test, optionally with explicit arguments.
+struct FixtureUse {
+    name: Ident,
+    args: Option<TokenStream>,
+}
+
+impl Parse for FixtureUse {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let args = if input.peek(syn::token::Paren) {
+            let args;
+            parenthesized!(args in input);
+            Some(args.parse()?)
+        } else {
+            None
+        };
+        Ok(FixtureUse { name, args })
+    }
+}
+
+/// The input of `__per_case_tests!` as generated by `test_suite!`.
+pub struct PerCaseTests {
+    fixtures: Vec<FixtureDef>,
+    prelude: TokenStream,
+    attrs: TokenStream,
+    name: Ident,
+    uses: Vec<FixtureUse>,
+    ret: TokenStream,
+    body: TokenStream,
+}
+
+impl Parse for PerCaseTests {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<kw::fixtures>()?;
+        let fixtures_content;
+        bracketed!(fixtures_content in input);
+        let mut fixtures = Vec::new();
+        while !fixtures_content.is_empty() {
+            fixtures.push(fixtures_content.parse()?);
+        }
+
+        input.parse::<kw::prelude>()?;
+        let prelude;
+        bracketed!(prelude in input);
+
+        input.parse::<kw::test>()?;
+        let attrs;
+        bracketed!(attrs in input);
+        let name = input.parse()?;
+        let uses;
+        parenthesized!(uses in input);
+        let uses = Punctuated::<FixtureUse, Token![,]>::parse_terminated(&uses)?;
+        let ret;
+        bracketed!(ret in input);
+
+        Ok(PerCaseTests {
+            fixtures,
+            prelude: prelude.parse()?,
+            attrs: attrs.parse()?,
+            name,
+            uses: uses.into_iter().collect(),
+            ret: ret.parse()?,
+            body: input.parse()?,
+        })
+    }
+}
+
+/// A way of injecting a fixture into a generated test function.
+#[derive(Clone)]
+struct Injection {
+    name_suffix: String,
+    tokens: TokenStream,
+}
+
+/// The injections of a fixture into the generated test functions.
+enum Injections {
+    /// The fixture is injected as written. Without explicit arguments it iterates over its parameterisations at runtime.
+    Unchanged { injection: Injection, is_dynamic: bool },
+    /// Each test function receives one of the statically known parameterisations.
+    Cases(Vec<Injection>),
+}
+
+impl Injections {
+    fn get(&self, index: usize) -> &Injection {
+        match *self {
+            Injections::Unchanged { ref injection, .. } => injection,
+            Injections::Cases(ref cases) => &cases[index],
+        }
+    }
+
+    fn len(&self) -> usize {
+        match *self {
+            Injections::Unchanged { .. } => 1,
+            Injections::Cases(ref cases) => cases.len(),
+        }
+    }
+}
+
+impl PerCaseTests {
+    fn injections(&self, fixture_use: &FixtureUse) -> Injections {
+        let name = &fixture_use.name;
+        let unchanged = |tokens| Injections::Unchanged {
+            injection: Injection {
+                name_suffix: String::new(),
+                tokens,
+            },
+            is_dynamic: fixture_use.args.is_none(),
+        };
+        if let Some(ref args) = fixture_use.args {
+            return unchanged(quote!(#name(#args)));
+        }
+
+        let fixture = self.fixtures.iter().find(|fixture| fixture.name == *name);
+        match fixture.and_then(FixtureDef::static_cases) {
+            // the generated test selects the case by its index so that it keeps its label
+            Some(cases) => Injections::Cases(
+                cases
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, name_suffix)| Injection {
+                        name_suffix,
+                        tokens: quote!(#name[#index]),
+                    })
+                    .collect(),
+            ),
+            None => unchanged(quote!(#name)),
+        }
+    }
+
+    /// Returns the built-in combination strategy selected by a `#[combine(...)]` attribute
+    /// and whether it is the cartesian product.
+    fn combinator(&self) -> syn::Result<(combinations::Combinator, bool)> {
+        let attrs = Attribute::parse_outer.parse2(self.attrs.clone())?;
+        let combine = match attrs.iter().find(|attr| attr.path().is_ident("combine")) {
+            Some(combine) => combine,
+            None => return Ok((combinations::product, true)),
+        };
+        let strategy: Path = combine.parse_args()?;
+        if strategy.is_ident("product") {
+            Ok((combinations::product, true))
+        } else if strategy.is_ident("zip") {
+            Ok((combinations::zip, false))
+        } else if strategy.is_ident("pairwise") {
+            Ok((combinations::pairwise, false))
+        } else {
+            Err(syn::Error::new_spanned(
+                strategy,
+                "`#[per_case]` tests only support the combination strategies `product`, `zip` and `pairwise`",
+            ))
+        }
+    }
+
+    pub fn expand(self) -> syn::Result<TokenStream> {
+        let (combinator, is_product) = self.combinator()?;
+        let injections: Vec<Injections> = self.uses.iter().map(|fixture_use| self.injections(fixture_use)).collect();
+
+        // fixtures iterating over their cases at runtime can only be combined with the others by the cartesian product
+        if !is_product {
+            let dynamic = self.uses.iter().zip(&injections).find(|&(_, injections)| match *injections {
+                Injections::Unchanged { is_dynamic, .. } => is_dynamic,
+                Injections::Cases(_) => false,
+            });
+            if let Some((fixture_use, _)) = dynamic {
+                return Err(syn::Error::new_spanned(
+                    &fixture_use.name,
+                    "The cases of the fixture are not statically known and can only be combined with `product`",
+                ));
+            }
+        }
+
+        let sizes: Vec<usize> = injections.iter().map(Injections::len).collect();
+        let combinations: Vec<Vec<&Injection>> = combinator(&sizes)
+            .into_iter()
+            .map(|combination| {
+                combination
+                    .into_iter()
+                    .zip(&injections)
+                    .map(|(index, injections)| injections.get(index))
+                    .collect()
+            })
+            .collect();
+
+        let PerCaseTests {
+            ref prelude,
+            ref attrs,
+            ref name,
+            ref ret,
+            ref body,
+            ..
+        } = self;
+        let case_count = combinations.len();
+        let tests = combinations.into_iter().enumerate().map(|(index, combination)| {
+            let suffix: String = combination.iter().map(|injection| injection.name_suffix.as_str()).collect();
+            let case_name = Ident::new(&format!("case_{}{}", index, suffix), Span::call_site());
+            let uses = combination.iter().map(|injection| &injection.tokens);
+            quote! {
+                ::galvanic_test::test!(@attrs [#prelude] [] [] #attrs #case_name | #(#uses),* | #ret #body);
+            }
+        });
+
+        Ok(quote! {
+            mod #name {
+                #[allow(unused_imports)]
+                use super::*;
+
+                pub(super) const __GALVANIC_CASES: usize = #case_count;
+
+                #(#tests)*
+            }
+        })
+    }
+}
+
+/// Describes an argument of a parameterisation by its literals so that it can be used in an identifier.
+fn describe(arg: &Expr) -> String {
+    fn collect_literals(tokens: TokenStream, description: &mut Vec<String>) {
+        let mut negated = false;
+        for token in tokens {
+            match token {
+                TokenTree::Literal(literal) => {
+                    let literal = literal.to_string();
+                    let literal = literal.trim_matches(|c| c == '"' || c == '\'');
+                    description.push(format!("{}{}", if negated { "neg" } else { "" }, literal));
+                    negated = false;
+                }
+                TokenTree::Punct(ref punct) if punct.as_char() == '-' => negated = true,
+                TokenTree::Group(group) => collect_literals(group.stream(), description),
+                _ => negated = false,
+            }
+        }
+    }
+
+    let tokens = quote!(#arg);
+    let mut literals = Vec::new();
+    collect_literals(tokens.clone(), &mut literals);
+    let description = if literals.is_empty() {
+        tokens.to_string()
+    } else {
+        literals.join("_")
+    };
+    sanitize(&description)
+}
+
+/// Converts `text` to a snake case string consisting of ASCII alphanumerics and underscores only.
+fn sanitize(text: &str) -> String {
+    let mut sanitized = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            sanitized.push(c.to_ascii_lowercase());
+        } else if !sanitized.ends_with('_') {
+            sanitized.push('_');
+        }
+    }
+    sanitized.trim_matches('_').to_string()
+}
@@ -0,0 +1,202 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The parameterisations of a fixture.
+
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+/// The expected outcome of executing a test with a parameterisation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expectation {
+    /// The test is expected to pass.
+    Pass,
+    /// The test is expected to fail, i.e., to panic or to return an error.
+    Fail,
+    /// The test is expected to panic with a message containing the given text.
+    Panic(String),
+    /// The test is not executed for the given reason.
+    Skip(String),
+}
+
+impl Expectation {
+    /// The expected outcome of a test combining several parameterisations.
+    ///
+    /// Skipping a parameterisation skips the combination, otherwise any expected failure applies.
+    pub fn of_combination(expectations: &[&Expectation]) -> Expectation {
+        let find = |is_match: fn(&Expectation) -> bool| expectations.iter().find(|expectation| is_match(expectation));
+        find(|expectation| matches!(expectation, Expectation::Skip(_)))
+            .or_else(|| find(|expectation| matches!(expectation, Expectation::Panic(_))))
+            .or_else(|| find(|expectation| *expectation == Expectation::Fail))
+            .map_or(Expectation::Pass, |&expectation| expectation.clone())
+    }
+}
+
+/// Proposes simpler cases than the one with the given parameters for which a failing test may still fail.
+type ShrinkFn<P> = dyn Fn(&P) -> Vec<Case<P>> + Send + Sync;
+
+struct Shrinker<P>(Arc<ShrinkFn<P>>);
+
+impl<P> Clone for Shrinker<P> {
+    fn clone(&self) -> Self {
+        Shrinker(self.0.clone())
+    }
+}
+
+impl<P> Debug for Shrinker<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Shrinker")
+    }
+}
+
+// the shrinker does not distinguish cases
+impl<P> PartialEq for Shrinker<P> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// A single parameterisation of a fixture, optionally identified by a human-readable label.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case<P> {
+    label: Option<String>,
+    /// Whether the fixture is shown in the description of the case in addition to the label.
+    shows_fixture: bool,
+    params: P,
+    expectation: Expectation,
+    seed: Option<u64>,
+    shrinker: Option<Shrinker<P>>,
+}
+
+impl<P> Case<P> {
+    /// Creates an unlabelled case from the fixture's parameters.
+    pub fn new(params: P) -> Self {
+        Case {
+            label: None,
+            shows_fixture: true,
+            params,
+            expectation: Expectation::Pass,
+            seed: None,
+            shrinker: None,
+        }
+    }
+
+    /// Creates a case from the fixture's parameters which is identified by `label`.
+    pub fn labelled<L: Into<String>>(label: L, params: P) -> Self {
+        Case {
+            label: Some(label.into()),
+            shows_fixture: true,
+            params,
+            expectation: Expectation::Pass,
+            seed: None,
+            shrinker: None,
+        }
+    }
+
+    /// Creates a case from the fixture's parameters which is described by its `id` alone, e.g., the path of a file.
+    pub fn identified<I: Into<String>>(id: I, params: P) -> Self {
+        Case {
+            shows_fixture: false,
+            ..Case::labelled(id, params)
+        }
+    }
+
+    /// Expects the test to fail with this case. A passing test is reported as failure.
+    pub fn xfail(mut self) -> Self {
+        self.expectation = Expectation::Fail;
+        self
+    }
+
+    /// Expects the test to panic with a message containing `message` with this case.
+    pub fn should_panic<M: Into<String>>(mut self, message: M) -> Self {
+        self.expectation = Expectation::Panic(message.into());
+        self
+    }
+
+    /// Reports the case as skipped for the given `reason` instead of executing the test.
+    pub fn skip<R: Into<String>>(mut self, reason: R) -> Self {
+        self.expectation = Expectation::Skip(reason.into());
+        self
+    }
+
+    /// Marks the case as generated by a random generator with the given `seed`.
+    #[doc(hidden)]
+    pub fn generated_with(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sets the function proposing simpler cases if the test fails with this case, see `shrink`.
+    ///
+    /// The proposed cases need shrinkers of their own to be shrunk further.
+    #[doc(hidden)]
+    pub fn shrunk_by<S>(mut self, shrinker: S) -> Self
+    where
+        S: Fn(&P) -> Vec<Case<P>> + Send + Sync + 'static,
+    {
+        self.shrinker = Some(Shrinker(Arc::new(shrinker)));
+        self
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    pub fn params(&self) -> &P {
+        &self.params
+    }
+
+    pub fn expectation(&self) -> &Expectation {
+        &self.expectation
+    }
+
+    /// The seed of the random generator which produced the case's parameters, see `params generate(...)`.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Simpler cases than this one, the simplest first, which are tried for finding a minimal failing parameterisation.
+    ///
+    /// Only generated cases can be shrunk, see `params generate(...)`.
+    pub fn shrink(&self) -> Vec<Case<P>> {
+        let shrinker = match self.shrinker {
+            Some(ref shrinker) => shrinker,
+            None => return Vec::new(),
+        };
+        (shrinker.0)(&self.params)
+            .into_iter()
+            .map(|case| Case {
+                label: None,
+                shows_fixture: true,
+                expectation: self.expectation.clone(),
+                seed: self.seed,
+                ..case
+            })
+            .collect()
+    }
+
+    pub fn into_params(self) -> P {
+        self.params
+    }
+
+    /// Describes the `fixture` created for this case in the output of a test.
+    pub fn describe<F: Debug>(&self, fixture: &F) -> String {
+        match self.label {
+            Some(ref id) if !self.shows_fixture => id.clone(),
+            Some(ref label) => format!("{:?} => {:?}", label, fixture),
+            None => format!("{:?}", fixture),
+        }
+    }
+}
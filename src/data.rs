@@ -0,0 +1,163 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Loading the cases of fixtures from files.
+//!
+//! A `params glob("tests/corpus/*.input")` block of a fixture creates a case for each file matching the pattern.
+//!
+//! A `params from_file("cases.csv")` block of a fixture deserialises each record of the file into the fixture's parameters.
+//! The format of the file is determined by its extension and requires the corresponding feature:
+//! * `csv` for CSV files with a header row,
+//! * `serde_json` for JSON files containing an array of records,
+//! * `toml` for TOML files containing an array of records as the value of the `cases` key.
+//!
+//! Records are deserialised into the tuple of the fixture's parameters, or into the single parameter of a fixture.
+
+#[cfg(feature = "serde")]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "serde")]
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::case::Case;
+
+/// Loads the cases of a fixture from the file at `path`, which is relative to the `manifest_dir` of the tested crate.
+///
+/// Each case is labelled as `path:line` with the line on which its record starts in the file.
+///
+/// # Panics
+/// If the file cannot be read or if one of its records cannot be deserialised into the fixture's parameters.
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub fn cases_from_file<P: DeserializeOwned>(manifest_dir: &str, path: &str) -> Vec<Case<P>> {
+    let file = Path::new(manifest_dir).join(path);
+    let content = fs::read_to_string(&file)
+        .unwrap_or_else(|error| panic!("Could not read the cases of the file `{}`: {}", file.display(), error));
+    let records: Result<Vec<(usize, P)>, String> = match extension(path) {
+        #[cfg(feature = "csv")]
+        "csv" => csv_records(&content),
+        #[cfg(feature = "serde_json")]
+        "json" => json_records(&content),
+        #[cfg(feature = "toml")]
+        "toml" => toml::from_str(&content).map_err(|error: toml::de::Error| error.to_string()).map(|cases: TomlCases<P>| {
+            cases.cases.into_iter().map(|case| (line(&content, case.span().start), case.into_inner())).collect()
+        }),
+        extension => unsupported_format(path, extension),
+    };
+    match records {
+        Ok(records) => labelled_records(path, records),
+        Err(error) => panic!("Could not deserialise the cases of the file `{}`: {}", file.display(), error),
+    }
+}
+
+#[cfg(feature = "csv")]
+fn csv_records<P: DeserializeOwned>(content: &str) -> Result<Vec<(usize, P)>, String> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let headers = reader.headers().map_err(|error| error.to_string())?.clone();
+    reader
+        .records()
+        .map(|record| {
+            let record = record.map_err(|error| error.to_string())?;
+            // the position of a record is the line on which it starts, a quoted field may span several lines
+            let line = record.position().map_or(0, |position| position.line() as usize);
+            record.deserialize(Some(&headers)).map(|params| (line, params)).map_err(|error| error.to_string())
+        })
+        .collect()
+}
+
+#[cfg(feature = "serde_json")]
+fn json_records<P: DeserializeOwned>(content: &str) -> Result<Vec<(usize, P)>, String> {
+    let records: Vec<&serde_json::value::RawValue> = serde_json::from_str(content).map_err(|error| error.to_string())?;
+    records
+        .into_iter()
+        .map(|record| {
+            // a raw value borrows its text from the content, so its offset is the distance between both
+            let line = line(content, record.get().as_ptr() as usize - content.as_ptr() as usize);
+            serde_json::from_str(record.get())
+                .map(|params| (line, params))
+                .map_err(|error| format!("line {}: {}", line, error))
+        })
+        .collect()
+}
+
+/// The cases of a TOML file are the array of the `cases` key.
+#[cfg(feature = "toml")]
+#[derive(serde::Deserialize)]
+struct TomlCases<P> {
+    cases: Vec<toml::Spanned<P>>,
+}
+
+/// The line of the byte `offset` in `content`, counting from 1.
+#[cfg(any(feature = "serde_json", feature = "toml"))]
+fn line(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+/// Without a feature for reading data files no file format is supported.
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+pub fn cases_from_file<P>(_manifest_dir: &str, path: &str) -> Vec<Case<P>> {
+    unsupported_format(path, extension(path))
+}
+
+/// Creates a case for each path matching the glob `pattern`, which is relative to the `manifest_dir` of the tested crate.
+///
+/// The cases are sorted by their paths and are identified by their path relative to `manifest_dir`.
+/// Their parameter is the absolute path.
+///
+/// # Panics
+/// If the pattern is invalid, a matching path cannot be read or the pattern does not match any path.
+#[doc(hidden)]
+pub fn cases_from_glob(manifest_dir: &str, pattern: &str) -> Vec<Case<PathBuf>> {
+    let manifest_dir = Path::new(manifest_dir);
+    // the manifest directory is matched literally
+    let absolute_pattern = format!("{}/{}", glob::Pattern::escape(&manifest_dir.to_string_lossy()), pattern);
+    let paths = glob::glob(&absolute_pattern)
+        .unwrap_or_else(|error| panic!("The glob pattern `{}` is invalid: {}", pattern, error));
+    let mut cases: Vec<_> = paths
+        .map(|path| {
+            let path = path.unwrap_or_else(|error| panic!("Could not match the glob pattern `{}`: {}", pattern, error));
+            let id = path.strip_prefix(manifest_dir).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+            Case::identified(id, path)
+        })
+        .collect();
+    if cases.is_empty() {
+        panic!("The glob pattern `{}` does not match any path in `{}`.", pattern, manifest_dir.display());
+    }
+    cases.sort_by(|a, b| a.params().cmp(b.params()));
+    cases
+}
+
+fn extension(path: &str) -> &str {
+    Path::new(path).extension().and_then(|extension| extension.to_str()).unwrap_or("")
+}
+
+#[cfg_attr(not(feature = "serde"), allow(dead_code))]
+fn labelled_records<P>(path: &str, records: Vec<(usize, P)>) -> Vec<Case<P>> {
+    records
+        .into_iter()
+        .map(|(line, record)| Case::labelled(format!("{}:{}", path, line), record))
+        .collect()
+}
+
+fn unsupported_format(path: &str, extension: &str) -> ! {
+    let feature = match extension {
+        "csv" => "csv",
+        "json" => "serde_json",
+        "toml" => "toml",
+        _ => panic!("The format of the file `{}` is not supported, use a `.csv`, `.json` or `.toml` file.", path),
+    };
+    panic!("Loading the cases of the file `{}` requires the `{}` feature of galvanic-test.", path, feature)
+}
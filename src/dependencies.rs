@@ -0,0 +1,280 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Support for fixtures depending on other fixtures.
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::{FixtureError, TestFixture};
+
+/// Names the type of the value set up by a fixture.
+///
+/// The trait is implemented by `fixture!` and allows fixtures to declare the values of other fixtures as inputs.
+pub trait FixtureValue {
+    type Value;
+}
+
+/// A fixture along with the fixtures depending on it, directly or transitively, as checked by `__acyclic_dependencies!`.
+///
+/// The fixtures are identified by their paths, e.g., `my_crate::tests::db_conn`.
+#[doc(hidden)]
+pub struct DependencyPath<'a> {
+    pub fixture: &'static str,
+    pub dependent: Option<&'a DependencyPath<'a>>,
+}
+
+impl DependencyPath<'_> {
+    /// Whether the fixture is one of the fixtures depending on it.
+    pub const fn is_cyclic(&self) -> bool {
+        let mut dependent = self.dependent;
+        while let Some(path) = dependent {
+            if str_eq(path.fixture, self.fixture) {
+                return true;
+            }
+            dependent = path.dependent;
+        }
+        false
+    }
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// A fixture set up as a dependency of other fixtures.
+struct SetUpDependency {
+    fixture_type: TypeId,
+    value: Rc<dyn Any>,
+    // dropping the fixture tears it down
+    fixture: Rc<dyn Any>,
+}
+
+/// The dependencies set up for a single test case.
+///
+/// Each dependency is set up at most once, even if several fixtures of the test depend on it.
+/// When dropped the dependencies are torn down in the reverse order of their setup.
+#[derive(Default)]
+pub struct Dependencies {
+    set_up: RefCell<Vec<SetUpDependency>>,
+}
+
+impl Dependencies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the value of the fixture `F`, setting up the fixture and its own dependencies if required.
+    pub fn get<F>(&self) -> Result<Rc<dyn Any>, FixtureError>
+    where
+        F: FixtureValue + TestFixture<'static, (), <F as FixtureValue>::Value> + 'static,
+        <F as FixtureValue>::Value: 'static,
+    {
+        let fixture_type = TypeId::of::<F>();
+        if let Some(dependency) = self
+            .set_up
+            .borrow()
+            .iter()
+            .find(|dependency| dependency.fixture_type == fixture_type)
+        {
+            return Ok(dependency.value.clone());
+        }
+
+        let mut fixture = F::new(&());
+        fixture.resolve_dependencies(self)?;
+        let value: Rc<dyn Any> = Rc::new(fixture.try_setup()?.into_val());
+        self.set_up.borrow_mut().push(SetUpDependency {
+            fixture_type,
+            value: value.clone(),
+            fixture: Rc::new(fixture),
+        });
+        Ok(value)
+    }
+
+    /// Returns the fixture `F` and its value if it has been set up as a dependency of another fixture.
+    fn set_up_fixture<F>(&self) -> Option<(Rc<F>, Rc<<F as FixtureValue>::Value>)>
+    where
+        F: FixtureValue + 'static,
+        <F as FixtureValue>::Value: 'static,
+    {
+        let fixture_type = TypeId::of::<F>();
+        let set_up = self.set_up.borrow();
+        let dependency = set_up.iter().find(|dependency| dependency.fixture_type == fixture_type)?;
+        Some((
+            dependency.fixture.clone().downcast().unwrap_or_else(|_| unreachable!("A fixture has an unexpected type")),
+            dependency.value.clone().downcast().unwrap_or_else(|_| unreachable!("A value has an unexpected type")),
+        ))
+    }
+}
+
+/// A fixture injected into a test which has already been set up as a dependency of another fixture of the test.
+///
+/// The test shares the fixture with the fixtures depending on it, i.e., the fixture is torn down along with the
+/// dependencies and the test receives a clone of its value, see `CloneInjectedValue`.
+#[doc(hidden)]
+pub struct InjectedDependency<F: FixtureValue> {
+    name: &'static str,
+    fixture: Rc<F>,
+    value: Rc<<F as FixtureValue>::Value>,
+}
+
+impl<F> InjectedDependency<F>
+where
+    F: FixtureValue + 'static,
+    <F as FixtureValue>::Value: 'static,
+{
+    /// Returns the fixture `F` named `name` if it has been set up as a dependency of another fixture of the test.
+    pub fn of(name: &'static str, dependencies: &Dependencies) -> Option<Self> {
+        dependencies
+            .set_up_fixture::<F>()
+            .map(|(fixture, value)| InjectedDependency { name, fixture, value })
+    }
+
+    pub fn fixture(&self) -> &F {
+        &self.fixture
+    }
+}
+
+/// Clones the value of an injected dependency for the test.
+///
+/// The method is preferred over the one of `UncloneableInjectedValue` as long as the value implements `Clone`.
+#[doc(hidden)]
+pub trait CloneInjectedValue<V> {
+    fn value(&self) -> V;
+}
+
+impl<F> CloneInjectedValue<<F as FixtureValue>::Value> for InjectedDependency<F>
+where
+    F: FixtureValue,
+    <F as FixtureValue>::Value: Clone,
+{
+    fn value(&self) -> <F as FixtureValue>::Value {
+        (*self.value).clone()
+    }
+}
+
+/// Rejects an injected dependency whose value cannot be cloned for the test.
+#[doc(hidden)]
+pub trait UncloneableInjectedValue<V> {
+    fn value(&self) -> V;
+}
+
+impl<F: FixtureValue> UncloneableInjectedValue<<F as FixtureValue>::Value> for &InjectedDependency<F> {
+    fn value(&self) -> <F as FixtureValue>::Value {
+        panic!(
+            "The fixture `{}` is injected into the test and is a dependency of another fixture of the test. \
+             Its value is shared with the test by a clone, which requires the value to implement `Clone`.",
+            self.name
+        );
+    }
+}
+
+impl Drop for Dependencies {
+    fn drop(&mut self) {
+        let set_up = self.set_up.get_mut();
+        while let Some(dependency) = set_up.pop() {
+            drop(dependency.value);
+            drop(dependency.fixture);
+        }
+    }
+}
+
+/// The values of the dependencies of a single fixture.
+#[derive(Default)]
+pub struct ResolvedDependencies {
+    values: Vec<(&'static str, TypeId, Rc<dyn Any>)>,
+}
+
+impl ResolvedDependencies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves the dependency on the fixture `F` named `name`.
+    pub fn resolve<F>(&mut self, name: &'static str, dependencies: &Dependencies) -> Result<(), FixtureError>
+    where
+        F: FixtureValue + TestFixture<'static, (), <F as FixtureValue>::Value> + 'static,
+        <F as FixtureValue>::Value: 'static,
+    {
+        let value = dependencies.get::<F>()?;
+        self.values.push((name, TypeId::of::<F>(), value));
+        Ok(())
+    }
+
+    /// Returns the value of the dependency on the fixture `F` named `name`.
+    ///
+    /// # Panics
+    /// If the dependency has not been resolved, i.e., the fixture was set up without `resolve_dependencies`.
+    pub fn get<F>(&self, name: &'static str) -> Rc<<F as FixtureValue>::Value>
+    where
+        F: FixtureValue + 'static,
+        <F as FixtureValue>::Value: 'static,
+    {
+        self.try_get::<F>().unwrap_or_else(|| {
+            panic!(
+                "The dependency `{}` has not been resolved. \
+                 Call `resolve_dependencies` before setting up a fixture with dependencies.",
+                name
+            )
+        })
+    }
+
+    /// Returns the value of the dependency on the fixture `F` if it has been resolved.
+    pub fn try_get<F>(&self) -> Option<Rc<<F as FixtureValue>::Value>>
+    where
+        F: FixtureValue + 'static,
+        <F as FixtureValue>::Value: 'static,
+    {
+        let fixture_type = TypeId::of::<F>();
+        self.values
+            .iter()
+            .find(|&&(_, t, _)| t == fixture_type)
+            .map(|(_, _, value)| {
+                value
+                    .clone()
+                    .downcast()
+                    .unwrap_or_else(|_| unreachable!("The value of a dependency has an unexpected type"))
+            })
+    }
+}
+
+impl fmt::Debug for ResolvedDependencies {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.values.iter().map(|&(name, _, _)| DependencyName(name)))
+            .finish()
+    }
+}
+
+struct DependencyName(&'static str);
+
+impl fmt::Debug for DependencyName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
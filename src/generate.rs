@@ -0,0 +1,546 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Strategies for generating random parameters of fixtures.
+//!
+//! A `params generate(n = 100) { ... }` block of a fixture produces `n` cases
+//! by drawing the fixture's parameters from the strategy returned by the block, e.g.,
+//! `(any::<i32>(), 0..10u8)` for a fixture with an `i32` and a `u8` parameter.
+//! The strategies of this module can be used in the block without importing them.
+//!
+//! The generated parameters depend only on the seed of the random generator.
+//! A seed is chosen randomly for each run of a test binary unless the fixture sets one with `seed = ...`.
+//! Setting the environment variable `GALVANIC_SEED` overrides the seed of all fixtures to replay a failing run.
+//!
+//! If a test fails with generated parameters, the strategies propose simpler parameters
+//! for which the test is executed again to find a minimal failing parameterisation.
+
+use std::collections::hash_map::RandomState;
+use std::env;
+use std::hash::{BuildHasher, Hasher};
+use std::marker::PhantomData;
+use std::ops::{Range, RangeInclusive};
+use std::sync::{Arc, OnceLock};
+
+use crate::case::Case;
+
+#[cfg(feature = "proptest")]
+mod proptest_strategy;
+#[cfg(feature = "quickcheck")]
+mod quickcheck_arbitrary;
+
+#[cfg(feature = "proptest")]
+pub use self::proptest_strategy::{from_proptest, FromProptest};
+#[cfg(feature = "quickcheck")]
+pub use self::quickcheck_arbitrary::{from_arbitrary, FromArbitrary};
+
+/// The environment variable setting the seed for generating the parameters of all fixtures.
+const SEED_VAR: &str = "GALVANIC_SEED";
+
+/// A deterministic pseudo-random number generator (SplitMix64).
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..bound`. The `bound` must not be 0.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        ((u128::from(self.next_u64()) * u128::from(bound)) >> 64) as u64
+    }
+
+    /// A number in `[0, 1)`.
+    pub fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A number in `start..start + width` where `width` is at most 2^64.
+    fn in_range(&mut self, start: i128, width: u128) -> i128 {
+        let offset = if width > u128::from(u64::MAX) {
+            self.next_u64()
+        } else {
+            self.below(width as u64)
+        };
+        start + i128::from(offset)
+    }
+}
+
+/// Generates random values of a fixture's parameters.
+pub trait Strategy {
+    type Value;
+
+    fn generate(&self, rng: &mut Rng) -> Self::Value;
+
+    /// Simpler values than `value` which the strategy could have generated, the simplest first.
+    ///
+    /// The candidates are used for finding a minimal parameterisation for which a test fails.
+    fn shrink(&self, _value: &Self::Value) -> Vec<Self::Value> {
+        Vec::new()
+    }
+}
+
+/// Types for which `any` provides a strategy covering all values of the type.
+pub trait Arbitrary: Sized {
+    fn arbitrary(rng: &mut Rng) -> Self;
+
+    /// Simpler values than `self`, the simplest first, see `Strategy::shrink`.
+    fn shrink(&self) -> Vec<Self> {
+        Vec::new()
+    }
+}
+
+/// Candidates for shrinking the integer `value` towards `target`: the target itself,
+/// then values halving the remaining distance to `value`, similar to a binary search.
+fn shrink_integer(value: i128, target: i128) -> Vec<i128> {
+    let mut candidates = Vec::new();
+    let mut distance = value - target;
+    while distance != 0 {
+        candidates.push(value - distance);
+        distance /= 2;
+    }
+    candidates
+}
+
+/// Candidates for shrinking a sequence of `len` elements to the ranges of its elements,
+/// which are at least `min_len` elements long: the shortest first, then its halves, then dropping the last or first element.
+fn shorter_ranges(len: usize, min_len: usize) -> Vec<Range<usize>> {
+    let mut candidates: Vec<Range<usize>> = Vec::new();
+    for candidate in [0..min_len, 0..len / 2, len / 2..len, 0..len.saturating_sub(1), 1..len] {
+        let is_valid = candidate.start <= candidate.end && candidate.len() < len && candidate.len() >= min_len;
+        if is_valid && !candidates.contains(&candidate) {
+            candidates.push(candidate);
+        }
+    }
+    candidates
+}
+
+/// Candidates for shrinking a vector by making it shorter or by shrinking one of its elements.
+fn shrink_vec<T: Clone, E: Fn(&T) -> Vec<T>>(values: &[T], min_len: usize, shrink_element: E) -> Vec<Vec<T>> {
+    let mut candidates: Vec<Vec<T>> = shorter_ranges(values.len(), min_len)
+        .into_iter()
+        .map(|range| values[range].to_vec())
+        .collect();
+    for (index, value) in values.iter().enumerate() {
+        for element in shrink_element(value) {
+            let mut candidate = values.to_vec();
+            candidate[index] = element;
+            candidates.push(candidate);
+        }
+    }
+    candidates
+}
+
+/// The strategy generating arbitrary values of `T`, see `any`.
+pub struct Any<T>(PhantomData<fn() -> T>);
+
+/// Generates arbitrary values of `T`, e.g., any `i32` by `any::<i32>()`.
+pub fn any<T: Arbitrary>() -> Any<T> {
+    Any(PhantomData)
+}
+
+impl<T: Arbitrary> Strategy for Any<T> {
+    type Value = T;
+
+    fn generate(&self, rng: &mut Rng) -> T {
+        T::arbitrary(rng)
+    }
+
+    fn shrink(&self, value: &T) -> Vec<T> {
+        value.shrink()
+    }
+}
+
+macro_rules! integer_strategies {
+    ($($int:ty),*) => {$(
+        impl Arbitrary for $int {
+            fn arbitrary(rng: &mut Rng) -> Self {
+                rng.next_u64() as $int
+            }
+
+            fn shrink(&self) -> Vec<Self> {
+                shrink_integer(*self as i128, 0).into_iter().map(|candidate| candidate as $int).collect()
+            }
+        }
+
+        impl Strategy for Range<$int> {
+            type Value = $int;
+
+            fn generate(&self, rng: &mut Rng) -> $int {
+                assert!(self.start < self.end, "Cannot generate a value of the empty range {:?}.", self);
+                let width = (self.end as i128 - self.start as i128) as u128;
+                rng.in_range(self.start as i128, width) as $int
+            }
+
+            /// Shrinks towards the value of the range which is closest to 0.
+            fn shrink(&self, value: &$int) -> Vec<$int> {
+                let zero: $int = 0;
+                let target = zero.clamp(self.start, self.end - 1);
+                shrink_integer(*value as i128, target as i128).into_iter().map(|candidate| candidate as $int).collect()
+            }
+        }
+
+        impl Strategy for RangeInclusive<$int> {
+            type Value = $int;
+
+            fn generate(&self, rng: &mut Rng) -> $int {
+                assert!(self.start() <= self.end(), "Cannot generate a value of the empty range {:?}.", self);
+                let width = (*self.end() as i128 - *self.start() as i128) as u128 + 1;
+                rng.in_range(*self.start() as i128, width) as $int
+            }
+
+            /// Shrinks towards the value of the range which is closest to 0.
+            fn shrink(&self, value: &$int) -> Vec<$int> {
+                let zero: $int = 0;
+                let target = zero.clamp(*self.start(), *self.end());
+                shrink_integer(*value as i128, target as i128).into_iter().map(|candidate| candidate as $int).collect()
+            }
+        }
+    )*};
+}
+
+integer_strategies!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+macro_rules! float_strategies {
+    ($($float:ty),*) => {$(
+        impl Strategy for Range<$float> {
+            type Value = $float;
+
+            fn generate(&self, rng: &mut Rng) -> $float {
+                assert!(self.start < self.end, "Cannot generate a value of the empty range {:?}.", self);
+                let value = self.start + (self.end - self.start) * rng.unit() as $float;
+                // rounding may hit the excluded end of the range
+                if value < self.end { value } else { self.start }
+            }
+
+            /// Shrinks towards the value of the range which is closest to 0 and to whole numbers.
+            fn shrink(&self, value: &$float) -> Vec<$float> {
+                let target = if self.start > 0.0 { self.start } else if self.end <= 0.0 { value.ceil() } else { 0.0 };
+                let mut candidates = Vec::new();
+                for candidate in [target, value.trunc()] {
+                    if candidate != *value && self.contains(&candidate) && !candidates.contains(&candidate) {
+                        candidates.push(candidate);
+                    }
+                }
+                candidates
+            }
+        }
+    )*};
+}
+
+float_strategies!(f32, f64);
+
+impl Arbitrary for bool {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        rng.next_u64() & 1 == 1
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        if *self {
+            vec![false]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl Arbitrary for char {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        loop {
+            // surrogates are not valid chars and are drawn again
+            if let Some(c) = char::from_u32(rng.below(0x11_0000) as u32) {
+                return c;
+            }
+        }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        if *self == 'a' {
+            Vec::new()
+        } else {
+            vec!['a']
+        }
+    }
+}
+
+/// The maximal length of arbitrary strings and vectors.
+const MAX_ARBITRARY_LEN: u64 = 32;
+
+impl Arbitrary for String {
+    /// Generates strings of printable ASCII characters to keep the parameterisations readable.
+    fn arbitrary(rng: &mut Rng) -> Self {
+        let len = rng.below(MAX_ARBITRARY_LEN + 1);
+        (0..len).map(|_| char::from(b' ' + rng.below(95) as u8)).collect()
+    }
+
+    /// Shrinks to shorter strings only as simpler characters rarely help to understand a failure.
+    fn shrink(&self) -> Vec<Self> {
+        let chars: Vec<char> = self.chars().collect();
+        shorter_ranges(chars.len(), 0)
+            .into_iter()
+            .map(|range| chars[range].iter().collect())
+            .collect()
+    }
+}
+
+impl<T: Arbitrary + Clone> Arbitrary for Vec<T> {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        let len = rng.below(MAX_ARBITRARY_LEN + 1);
+        (0..len).map(|_| T::arbitrary(rng)).collect()
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        shrink_vec(self, 0, T::shrink)
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Option<T> {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        if bool::arbitrary(rng) {
+            Some(T::arbitrary(rng))
+        } else {
+            None
+        }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        match self {
+            Some(value) => Some(None).into_iter().chain(value.shrink().into_iter().map(Some)).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// The strategy always generating the same value.
+#[derive(Debug, Clone)]
+pub struct Just<T: Clone>(pub T);
+
+impl<T: Clone> Strategy for Just<T> {
+    type Value = T;
+
+    fn generate(&self, _rng: &mut Rng) -> T {
+        self.0.clone()
+    }
+}
+
+/// The strategy choosing one of the given values, see `select`.
+#[derive(Debug, Clone)]
+pub struct Select<T: Clone + PartialEq>(Vec<T>);
+
+/// Generates one of the given `values`, which must not be empty.
+pub fn select<T: Clone + PartialEq>(values: Vec<T>) -> Select<T> {
+    assert!(!values.is_empty(), "Cannot select a value of an empty list of values.");
+    Select(values)
+}
+
+impl<T: Clone + PartialEq> Strategy for Select<T> {
+    type Value = T;
+
+    fn generate(&self, rng: &mut Rng) -> T {
+        self.0[rng.below(self.0.len() as u64) as usize].clone()
+    }
+
+    /// Shrinks towards the values given first.
+    fn shrink(&self, value: &T) -> Vec<T> {
+        match self.0.iter().position(|candidate| candidate == value) {
+            Some(index) => self.0[..index].to_vec(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// The strategy generating vectors, see `vec`.
+pub struct VecOf<S> {
+    element: S,
+    len: Range<usize>,
+}
+
+/// Generates vectors whose length is within `len` and whose elements are generated by `element`.
+pub fn vec<S: Strategy>(element: S, len: Range<usize>) -> VecOf<S> {
+    VecOf { element, len }
+}
+
+impl<S: Strategy> Strategy for VecOf<S>
+where
+    S::Value: Clone,
+{
+    type Value = Vec<S::Value>;
+
+    fn generate(&self, rng: &mut Rng) -> Vec<S::Value> {
+        let len = self.len.generate(rng);
+        (0..len).map(|_| self.element.generate(rng)).collect()
+    }
+
+    fn shrink(&self, values: &Vec<S::Value>) -> Vec<Vec<S::Value>> {
+        shrink_vec(values, self.len.start, |value| self.element.shrink(value))
+    }
+}
+
+macro_rules! tuple_strategies {
+    ($(($($strategy:ident),+))*) => {$(
+        #[allow(non_snake_case)]
+        impl<$($strategy: Strategy),+> Strategy for ($($strategy,)+)
+        where
+            $($strategy::Value: Clone),+
+        {
+            type Value = ($($strategy::Value,)+);
+
+            fn generate(&self, rng: &mut Rng) -> Self::Value {
+                let ($(ref $strategy,)+) = *self;
+                ($($strategy.generate(rng),)+)
+            }
+
+            /// Shrinks one element of the tuple at a time.
+            fn shrink(&self, value: &Self::Value) -> Vec<Self::Value> {
+                let mut candidates = Vec::new();
+                tuple_strategies!(@shrink self value candidates [] [$($strategy)+]);
+                candidates
+            }
+        }
+    )*};
+    ( @shrink $self_:ident $value:ident $candidates:ident [$($before:ident)*] [] ) => {};
+    ( @shrink $self_:ident $value:ident $candidates:ident [$($before:ident)*] [$current:ident $($after:ident)*] ) => {
+        {
+            let ($(ref $before,)* ref $current, $(ref $after,)*) = *$self_;
+            let _ = ($($before,)* $($after,)*);
+            let ($(ref $before,)* ref current_value, $(ref $after,)*) = *$value;
+            for candidate in $current.shrink(current_value) {
+                $candidates.push(($($before.clone(),)* candidate, $($after.clone(),)*));
+            }
+        }
+        tuple_strategies!(@shrink $self_ $value $candidates [$($before)* $current] [$($after)*]);
+    };
+}
+
+tuple_strategies! {
+    (A)
+    (A, B)
+    (A, B, C)
+    (A, B, C, D)
+    (A, B, C, D, E)
+    (A, B, C, D, E, F)
+    (A, B, C, D, E, F, G)
+    (A, B, C, D, E, F, G, H)
+}
+
+/// Generates the cases of a fixture with a `params generate(...)` block.
+///
+/// Every `Strategy` generates cases which are shrunk by the strategy.
+/// Sources of parameters with their own way of shrinking, e.g., proptest, implement this trait directly.
+pub trait GenerateCases {
+    type Value;
+
+    /// Generates a case whose shrinker proposes simpler cases if a test fails with it.
+    fn generate_case(this: &Arc<Self>, rng: &mut Rng) -> Case<Self::Value>;
+}
+
+impl<S> GenerateCases for S
+where
+    S: Strategy + Send + Sync + 'static,
+{
+    type Value = S::Value;
+
+    fn generate_case(this: &Arc<Self>, rng: &mut Rng) -> Case<S::Value> {
+        shrinkable_case(this, this.generate(rng))
+    }
+}
+
+/// A case with the `value` which is shrunk by the `strategy`.
+fn shrinkable_case<S>(strategy: &Arc<S>, value: S::Value) -> Case<S::Value>
+where
+    S: Strategy + Send + Sync + 'static,
+{
+    let strategy = strategy.clone();
+    Case::new(value).shrunk_by(move |value| {
+        strategy
+            .shrink(value)
+            .into_iter()
+            .map(|candidate| shrinkable_case(&strategy, candidate))
+            .collect()
+    })
+}
+
+/// The seed used by fixtures without a seed of their own, chosen once per run of the test binary.
+fn run_seed() -> u64 {
+    static SEED: OnceLock<u64> = OnceLock::new();
+    *SEED.get_or_init(|| RandomState::new().build_hasher().finish())
+}
+
+/// The seed set by `GALVANIC_SEED` to replay a previous run.
+fn replay_seed() -> Option<u64> {
+    let seed = env::var(SEED_VAR).ok()?;
+    match seed.trim().parse() {
+        Ok(seed) => Some(seed),
+        Err(_) => panic!("The environment variable {} must be an unsigned integer, but is {:?}.", SEED_VAR, seed),
+    }
+}
+
+/// Derives the random generator of a fixture from the seed,
+/// so that fixtures with the same strategies do not generate the same parameters.
+fn fixture_rng(seed: u64, fixture: &str) -> Rng {
+    // FNV-1a, as it is stable between runs in contrast to the hashers of the standard library
+    let hash = fixture
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3));
+    Rng::new(seed ^ hash)
+}
+
+/// Generates the cases of a fixture with a `params generate(...)` block.
+#[doc(hidden)]
+pub struct Generator {
+    fixture: &'static str,
+    count: usize,
+    seed: Option<u64>,
+}
+
+impl Generator {
+    pub fn new(fixture: &'static str) -> Self {
+        Generator {
+            fixture,
+            count: 100,
+            seed: None,
+        }
+    }
+
+    /// Sets the number of generated cases.
+    pub fn n(mut self, count: usize) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Sets the seed of the random generator unless `GALVANIC_SEED` is set.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn cases<G: GenerateCases>(self, generator: G) -> impl Iterator<Item = Case<G::Value>> {
+        let seed = replay_seed().or(self.seed).unwrap_or_else(run_seed);
+        let mut rng = fixture_rng(seed, self.fixture);
+        let generator = Arc::new(generator);
+        (0..self.count).map(move |_| G::generate_case(&generator, &mut rng).generated_with(seed))
+    }
+}
@@ -0,0 +1,123 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Generating parameters with proptest strategies.
+//!
+//! Proptest shrinks a value through its value tree, which simplifies the value step by step
+//! and makes it more complicated again if the simplified value does not fail the test.
+//! As the value trees cannot be cloned, a case remembers the seed of its tree and the steps taken so far
+//! and replays them to propose the next simpler cases.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use proptest::strategy::{Strategy as ProptestStrategy, ValueTree};
+use proptest::test_runner::{Config, RngAlgorithm, TestRng, TestRunner};
+
+use super::{GenerateCases, Rng};
+use crate::case::Case;
+
+/// The maximal number of times a simplified value is made more complicated again while proposing simpler cases.
+const MAX_COMPLICATIONS: usize = 64;
+
+/// Generates the parameters of a fixture with a proptest strategy, see `from_proptest`.
+#[derive(Debug, Clone)]
+pub struct FromProptest<S>(S);
+
+/// Generates the parameters of a fixture with the proptest `strategy`, shrinking them as proptest does.
+pub fn from_proptest<S: ProptestStrategy>(strategy: S) -> FromProptest<S> {
+    FromProptest(strategy)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    Simplify,
+    Complicate,
+}
+
+/// Identifies a state of a value tree by the seed of the tree and the steps applied to it.
+#[derive(Debug, Clone)]
+struct TreePath {
+    seed: [u8; 32],
+    steps: Vec<Step>,
+}
+
+impl<S: ProptestStrategy> FromProptest<S> {
+    fn tree(&self, path: &TreePath) -> S::Tree {
+        let rng = TestRng::from_seed(RngAlgorithm::ChaCha, &path.seed);
+        let mut runner = TestRunner::new_with_rng(Config::default(), rng);
+        let mut tree = match self.0.new_tree(&mut runner) {
+            Ok(tree) => tree,
+            Err(reason) => panic!("The proptest strategy could not generate parameters: {}", reason),
+        };
+        for step in &path.steps {
+            match step {
+                Step::Simplify => tree.simplify(),
+                Step::Complicate => tree.complicate(),
+            };
+        }
+        tree
+    }
+}
+
+impl<S> FromProptest<S>
+where
+    S: ProptestStrategy + Send + Sync + 'static,
+    S::Value: Debug,
+{
+    fn case(this: &Arc<Self>, path: TreePath, value: S::Value) -> Case<S::Value> {
+        let strategy = this.clone();
+        Case::new(value).shrunk_by(move |_| Self::simpler_cases(&strategy, &path))
+    }
+
+    /// The next simplification of the tree followed by its complications, which proptest would try
+    /// one after another as long as the previous one does not fail the test.
+    fn simpler_cases(this: &Arc<Self>, path: &TreePath) -> Vec<Case<S::Value>> {
+        let mut tree = this.tree(path);
+        let mut candidate = path.clone();
+        let mut candidates = Vec::new();
+        if !tree.simplify() {
+            return candidates;
+        }
+        candidate.steps.push(Step::Simplify);
+        candidates.push(Self::case(this, candidate.clone(), tree.current()));
+        for _ in 0..MAX_COMPLICATIONS {
+            if !tree.complicate() {
+                break;
+            }
+            candidate.steps.push(Step::Complicate);
+            candidates.push(Self::case(this, candidate.clone(), tree.current()));
+        }
+        candidates
+    }
+}
+
+impl<S> GenerateCases for FromProptest<S>
+where
+    S: ProptestStrategy + Send + Sync + 'static,
+    S::Value: Debug,
+{
+    type Value = S::Value;
+
+    fn generate_case(this: &Arc<Self>, rng: &mut Rng) -> Case<S::Value> {
+        let mut seed = [0; 32];
+        for chunk in seed.chunks_mut(8) {
+            chunk.copy_from_slice(&rng.next_u64().to_le_bytes());
+        }
+        let path = TreePath { seed, steps: Vec::new() };
+        let value = this.tree(&path).current();
+        Self::case(this, path, value)
+    }
+}
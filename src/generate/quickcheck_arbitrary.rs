@@ -0,0 +1,49 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Generating parameters with quickcheck's `Arbitrary` types.
+
+use std::marker::PhantomData;
+
+use quickcheck::{Arbitrary as QuickcheckArbitrary, Gen};
+
+use super::{Rng, Strategy};
+
+/// The size of quickcheck's generator, e.g., bounding the length of vectors. This is quickcheck's default size.
+const GEN_SIZE: usize = 100;
+
+/// The maximal number of simpler values proposed by `Arbitrary::shrink` which are tried in a single shrinking step.
+const MAX_SHRINK_CANDIDATES: usize = 64;
+
+/// Generates the parameters of a fixture with quickcheck, see `from_arbitrary`.
+pub struct FromArbitrary<T>(PhantomData<fn() -> T>);
+
+/// Generates the parameters of a fixture with quickcheck's `Arbitrary` implementation of `T`,
+/// shrinking them with `Arbitrary::shrink`.
+pub fn from_arbitrary<T: QuickcheckArbitrary>() -> FromArbitrary<T> {
+    FromArbitrary(PhantomData)
+}
+
+impl<T: QuickcheckArbitrary> Strategy for FromArbitrary<T> {
+    type Value = T;
+
+    fn generate(&self, rng: &mut Rng) -> T {
+        T::arbitrary(&mut Gen::from_size_and_seed(GEN_SIZE, rng.next_u64()))
+    }
+
+    fn shrink(&self, value: &T) -> Vec<T> {
+        value.shrink().take(MAX_SHRINK_CANDIDATES).collect()
+    }
+}
@@ -16,19 +16,128 @@
 use std::fmt::Debug;
 use std::ops::Drop;
 
+mod case;
+mod data;
+mod dependencies;
+pub mod generate;
+mod parallel;
+mod report;
+mod runtime;
+mod shrink;
+mod skip;
+mod snapshot;
+mod timeout;
+#[doc(hidden)]
+pub mod shared;
+
+pub use crate::case::{Case, Expectation};
+pub use galvanic_test_combinations as combinations;
+pub use crate::dependencies::{
+    CloneInjectedValue, Dependencies, DependencyPath, FixtureValue, InjectedDependency, ResolvedDependencies, UncloneableInjectedValue,
+};
+pub use crate::report::FixtureError;
+pub use crate::runtime::block_on;
+pub use crate::skip::skip;
+pub use crate::snapshot::assert_snapshot;
+#[doc(hidden)]
+pub use crate::data::{cases_from_file, cases_from_glob};
+#[doc(hidden)]
+pub use crate::parallel::run_parallel;
+#[doc(hidden)]
+pub use crate::report::{retried, CaseError, Recorded, TestReport};
+#[doc(hidden)]
+pub use crate::shrink::{shrink, shrinker, unerase, AnyCase, ErasedShrinker};
+#[doc(hidden)]
+pub use crate::shared::{SharedFixture, SuiteTest, SuiteTracker};
+#[doc(hidden)]
+pub use crate::skip::{skippable, Skip};
+#[doc(hidden)]
+pub use crate::snapshot::{SnapshotCase, SnapshotScope};
+#[doc(hidden)]
+pub use crate::timeout::{supervise, supervise_cases, TimedSection};
+#[doc(hidden)]
+pub use galvanic_test_macros::{__acyclic_dependencies, __dsl_error, __per_case_tests};
+pub use galvanic_test_macros::TestFixture;
+
+/// Attributes declaring fixtures and tests as plain functions.
+///
+/// The attributes are used by their path, e.g., `#[galvanic::test]` after `use galvanic_test::galvanic;`,
+/// as the names `fixture` and `test` are taken by the `fixture!` and `test!` macros.
+/// Fixtures and tests declared by the attributes and by the macros can be used together.
+pub mod galvanic {
+    pub use galvanic_test_macros::{fixture, teardown, test};
+}
+
+/// The tear down of a fixture declared by `#[galvanic::fixture]` without a `#[galvanic::teardown]` function.
+///
+/// A `#[galvanic::teardown]` function defines an inherent method of the same name which takes precedence.
+#[doc(hidden)]
+pub trait NoTearDown {
+    fn __galvanic_tear_down(&self) {}
+}
+
+impl<T: ?Sized> NoTearDown for T {}
+
+#[allow(drop_bounds)]
 pub trait TestFixture<'param, P, R>: Drop
 where
     P: Debug + 'static,
 {
     fn new(curried_params: &'param P) -> Self;
 
-    fn parameters() -> Option<Box<Iterator<Item = P>>>;
+    fn parameters() -> Option<Box<dyn Iterator<Item = P>>>;
 
-    fn setup(&mut self) -> FixtureBinding<Self, R>
+    /// The parameterisations of the fixture together with their labels.
+    fn cases() -> Option<Box<dyn Iterator<Item = Case<P>>>> {
+        Self::parameters().map(|parameters| Box::new(parameters.map(Case::new)) as Box<dyn Iterator<Item = Case<P>>>)
+    }
+
+    fn setup(&mut self) -> FixtureBinding<'_, Self, R>
     where
         Self: std::marker::Sized;
 
+    /// Sets up the fixture, returning an error instead of panicking if the setup of a fallible fixture fails.
+    fn try_setup(&mut self) -> Result<FixtureBinding<'_, Self, R>, FixtureError>
+    where
+        Self: std::marker::Sized,
+    {
+        Ok(self.setup())
+    }
+
     fn tear_down(&self) {}
+
+    /// The time the setup and the tear down of the fixture may take each, given by `timeout(duration)` in the fixture.
+    fn timeout() -> Option<std::time::Duration> {
+        None
+    }
+
+    /// Whether the fixture or one of the fixtures it depends on, directly or transitively, has a timeout.
+    #[doc(hidden)]
+    fn has_timeout() -> bool {
+        Self::timeout().is_some()
+    }
+
+    /// Whether all uses of the fixture share a single value, i.e., the fixture is declared with a `scope`.
+    #[doc(hidden)]
+    fn is_shared() -> bool {
+        false
+    }
+
+    /// Resolves the fixtures this fixture depends on before it is set up.
+    ///
+    /// Dependencies already set up for the current test case are reused.
+    fn resolve_dependencies(&mut self, _dependencies: &Dependencies) -> Result<(), FixtureError> {
+        Ok(())
+    }
+
+    /// Drops the fixture without tearing it down, e.g., as it is shared with the fixtures depending on it.
+    #[doc(hidden)]
+    fn discard(self)
+    where
+        Self: std::marker::Sized,
+    {
+        std::mem::forget(self);
+    }
 }
 
 pub struct FixtureBinding<'fixture, F: 'fixture, R> {
@@ -55,6 +164,54 @@ impl<'fixture, F: 'fixture, R> FixtureBinding<'fixture, F, R> {
 /// A `fixture!` requires a name, parameters and a
 #[macro_export(local_inner_macros)]
 macro_rules! fixture {
+    // the shared value is set up by the fixture declared with `-> $decl` which produces a `$ret_ty`,
+    // a fixture shared by a suite is torn down after the suite's last test injecting it, see `SuiteTracker`
+    ( @shared [$($suite:tt)*] $name:ident Value[$ret_ty:ty] Decl[$($decl:tt)*] { $($body:tt)* } ) => {
+        #[allow(non_camel_case_types)]
+        #[derive(Debug)]
+        pub struct $name<'param> {
+            _phantom: &'param (),
+        }
+
+        impl<'param> ::galvanic_test::TestFixture<'param, (), ::std::sync::Arc<$ret_ty>> for $name<'param> {
+            fn new(_phantom: &'param ()) -> Self {
+                Self { _phantom }
+            }
+            fn parameters() -> Option<Box<dyn Iterator<Item=()>>> {
+                Some(Box::new(Some(()).into_iter()))
+            }
+            fn is_shared() -> bool {
+                true
+            }
+            fn setup(&mut self) -> ::galvanic_test::FixtureBinding<'_, Self, ::std::sync::Arc<$ret_ty>> {
+                // the actual fixture shadows the shared one within this fn
+                fixture!($name() -> $($decl)* { $($body)* });
+
+                static SHARED: ::galvanic_test::SharedFixture<$ret_ty> =
+                    ::galvanic_test::SharedFixture::new(_galvanic__stringify!($name));
+                let value = SHARED.get_or_setup($($suite)*, || {
+                    let mut fixture = $name::new(&());
+                    let value = fixture.setup().into_val();
+                    (value, Box::new(fixture))
+                });
+                ::galvanic_test::FixtureBinding {
+                    val: value,
+                    params: self
+                }
+            }
+        }
+
+        fixture!(@impl_value $name Deps[] -> ::std::sync::Arc<$ret_ty>);
+        fixture!(@impl_drop $name);
+    };
+
+    ( scope session $name:ident () -> Result<$ret_ty:ty, $err_ty:ty> { $($body:tt)* } ) => {
+        fixture!(@shared [None] $name Value[$ret_ty] Decl[Result<$ret_ty, $err_ty>] { $($body)* });
+    };
+    ( scope session $name:ident () -> $ret_ty:ty { $($body:tt)* } ) => {
+        fixture!(@shared [None] $name Value[$ret_ty] Decl[$ret_ty] { $($body)* });
+    };
+
     ( @impl_drop $name:ident ) => {
         impl<'param> ::std::ops::Drop for $name<'param> {
             fn drop(&mut self) {
@@ -64,114 +221,543 @@ macro_rules! fixture {
         }
     };
 
-    ( @impl_struct $name:ident Params[$($param:ident : $param_ty:ty),*] Members[$($member:ident : $member_ty:ty),*] ) => {
+    ( @impl_value $name:ident Deps[$($dep:ident),*] -> $ret_ty:ty ) => {
+        impl ::galvanic_test::FixtureValue for $name<'_> {
+            type Value = $ret_ty;
+        }
+
+        ::galvanic_test::__acyclic_dependencies!($name [$($dep),*]);
+    };
+
+    ( @impl_struct $name:ident Hidden[$($hidden:ident : $hidden_ty:ty = $hidden_init:expr,)*] Params[$($param:ident : $param_ty:ty),*] Members[$($member:ident : $member_ty:ty),*] ) => {
         #[allow(non_camel_case_types)]
-        #[derive(Debug)]
         pub struct $name<'param> {
+            $($hidden: $hidden_ty,)*
             $(pub $param : &'param $param_ty,)*
             $($member : Option<$member_ty>,)*
         }
+
+        // hidden fields are not part of the parameterisation shown in failure messages
+        impl<'param> ::std::fmt::Debug for $name<'param> {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_struct(_galvanic__stringify!($name))
+                    $(.field(_galvanic__stringify!($param), &self.$param))*
+                    $(.field(_galvanic__stringify!($member), &self.$member))*
+                    .finish()
+            }
+        }
+    };
+
+    // the values are named after the fixtures, which may be structs deriving `TestFixture`
+    ( @dependency_values $self_:ident [$($dep:ident),*] ) => {
+        $(#[allow(non_snake_case)]
+        let $dep = $self_._dependencies.get::<$dep>(_galvanic__stringify!($dep));)*
+    };
+    // a fixture whose dependencies have not been resolved has not been set up and is not torn down
+    ( @dependency_values_or_return $self_:ident [$($dep:ident),*] ) => {
+        $(#[allow(non_snake_case)]
+        let $dep = match $self_._dependencies.try_get::<$dep>() {
+            Some(value) => value,
+            None => return,
+        };)*
+    };
+
+    ( @resolve_dependencies [] ) => { };
+    ( @resolve_dependencies [$($dep:ident),+] ) => {
+        fn resolve_dependencies(&mut self, dependencies: &::galvanic_test::Dependencies)
+                -> Result<(), ::galvanic_test::FixtureError> {
+            $(self._dependencies.resolve::<$dep>(_galvanic__stringify!($dep), dependencies)?;)*
+            Ok(())
+        }
+
+        // dependencies are set up along with the fixture so their timeouts have to be enforced as well
+        fn has_timeout() -> bool {
+            Self::timeout().is_some() $(|| $dep::has_timeout())*
+        }
+
+        // a fixture whose dependencies have not been resolved is not torn down, see `@dependency_values_or_return`
+        fn discard(mut self) {
+            self._dependencies = ::galvanic_test::ResolvedDependencies::new();
+        }
     };
 
-    ( @new_method Params[$param:ident : $param_ty:ty] Members[$($member:ident),*] ) => {
+    ( @new_method Hidden[$($hidden:ident = $hidden_init:expr,)*] Params[$param:ident : $param_ty:ty] Members[$($member:ident),*] ) => {
         fn new($param : &'param $param_ty) -> Self {
             Self {
+                $($hidden: $hidden_init,)*
                 $param,
                 $($member: None,)*
             }
         }
     };
-    ( @new_method Params[$($param:ident : $param_ty:ty),+] Members[$($member:ident),*] ) => {
+    ( @new_method Hidden[$($hidden:ident = $hidden_init:expr,)*] Params[$($param:ident : $param_ty:ty),+] Members[$($member:ident),*] ) => {
         fn new(&($(ref $param),*) : &'param ($($param_ty),*)) -> Self {
             Self {
+                $($hidden: $hidden_init,)*
                 $($param,)*
                 $($member: None,)*
             }
         }
     };
 
-    ( $name:ident ( ) -> $ret_ty:ty {
+    ( @timed_section $name:ident $self_:ident $activity:expr ) => {
+        ::galvanic_test::TimedSection::enter(Self::timeout(), || {
+            _galvanic__format!("{} of fixture `{}` with params {:?}", $activity, _galvanic__stringify!($name), $self_)
+        })
+    };
+
+    ( @setup_methods $name:ident $self_:ident $deps:tt Error[] -> $ret_ty:ty $setup_body:block ) => {
+        // the setup may end with `skip!`
+        #[allow(unreachable_code, clippy::diverging_sub_expression)]
+        fn setup(&mut $self_) -> ::galvanic_test::FixtureBinding<'_, Self, $ret_ty> {
+            let _timed_section = fixture!(@timed_section $name $self_ "setup");
+            fixture!(@dependency_values $self_ $deps);
+            let value = $setup_body;
+            ::galvanic_test::FixtureBinding {
+                val: value,
+                params: $self_
+            }
+        }
+    };
+    ( @setup_methods $name:ident $self_:ident $deps:tt Error[$err_ty:ty] -> $ret_ty:ty $setup_body:block ) => {
+        fn setup(&mut $self_) -> ::galvanic_test::FixtureBinding<'_, Self, $ret_ty> {
+            match $self_.try_setup() {
+                Ok(binding) => binding,
+                Err(error) => _galvanic__panic!("{}", error),
+            }
+        }
+        #[allow(unreachable_code, clippy::diverging_sub_expression)]
+        fn try_setup(&mut $self_)
+                -> Result<::galvanic_test::FixtureBinding<'_, Self, $ret_ty>, ::galvanic_test::FixtureError> {
+            let _timed_section = fixture!(@timed_section $name $self_ "setup");
+            fixture!(@dependency_values $self_ $deps);
+            let result: Result<$ret_ty, $err_ty> = $setup_body;
+            match result {
+                Ok(value) => Ok(::galvanic_test::FixtureBinding {
+                    val: value,
+                    params: $self_
+                }),
+                Err(error) => {
+                    $self_._setup_failed = true;
+                    Err(::galvanic_test::FixtureError::new(_galvanic__stringify!($name), &*$self_, &error))
+                }
+            }
+        }
+    };
+
+    ( @tear_down_method $name:ident $self_:ident $deps:tt Error[] $tear_down_body:block ) => {
+        fn tear_down(&$self_) {
+            let _timed_section = fixture!(@timed_section $name $self_ "tear_down");
+            fixture!(@dependency_values_or_return $self_ $deps);
+            $tear_down_body
+        }
+    };
+    ( @tear_down_method $name:ident $self_:ident $deps:tt Error[$err_ty:ty] $tear_down_body:block ) => {
+        fn tear_down(&$self_) {
+            // a fixture whose setup failed has nothing to tear down
+            if $self_._setup_failed {
+                return;
+            }
+            let _timed_section = fixture!(@timed_section $name $self_ "tear_down");
+            fixture!(@dependency_values_or_return $self_ $deps);
+            $tear_down_body
+        }
+    };
+
+    // splits the arguments of a fixture into dependencies on other fixtures and typed parameters
+    ( @args $name:ident [$($dep:ident,)*] [$($param:tt)*] ( $arg:ident : $arg_ty:ty $(, $($args:tt)*)? ) $($remainder:tt)* ) => {
+        fixture!(@args $name [$($dep,)*] [$($param)* $arg : $arg_ty,] ( $($($args)*)? ) $($remainder)*);
+    };
+    ( @args $name:ident [$($dep:ident,)*] [$($param:tt)*] ( $arg:ident $(, $($args:tt)*)? ) $($remainder:tt)* ) => {
+        fixture!(@args $name [$($dep,)* $arg,] [$($param)*] ( $($($args)*)? ) $($remainder)*);
+    };
+
+    ( @args $name:ident [] [$($param:tt)*] ( ) $($remainder:tt)* ) => {
+        fixture!(@error $name Hidden[] Deps[] Params[$($param)*] $($remainder)*);
+    };
+    ( @args $name:ident [$($dep:ident,)+] [$($param:tt)*] ( ) $($remainder:tt)* ) => {
+        fixture!(@error $name
+                 Hidden[_dependencies: ::galvanic_test::ResolvedDependencies = ::galvanic_test::ResolvedDependencies::new(),]
+                 Deps[$($dep),+] Params[$($param)*] $($remainder)*);
+    };
+    ( @args $name:ident $deps:tt $params:tt ( $($args:tt)* ) $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!([$($args)*]
+            "the arguments of a fixture must be typed parameters `name: Type` or fixtures it depends on, separated by commas");
+    };
+
+    // fallible fixtures remember a failed setup so that they are not torn down
+    ( @error $name:ident Hidden[$($hidden:tt)*] Deps $deps:tt Params $params:tt Error[] $($remainder:tt)* ) => {
+        fixture!(@define $name Hidden[$($hidden)*] Deps $deps Params $params Error[] $($remainder)*);
+    };
+    ( @error $name:ident Hidden[$($hidden:tt)*] Deps $deps:tt Params $params:tt Error[$err_ty:ty] $($remainder:tt)* ) => {
+        fixture!(@define $name Hidden[$($hidden)* _setup_failed: bool = false,] Deps $deps Params $params Error[$err_ty] $($remainder)*);
+    };
+
+    ( @define $name:ident Hidden[$($hidden:ident : $hidden_ty:ty = $hidden_init:expr,)*] Deps $deps:tt Params[]
+          Error $error:tt -> $ret_ty:ty {
+          $(timeout($timeout:expr))?
           $(members { $($member:ident : Option<$member_ty:ty>),* })*
           setup(& mut $self_setup:ident) $setup_body:block
           $(tear_down(&$self_td:ident) $tear_down_body:block)*
       }
     ) => {
-        fixture!(@impl_struct $name Params[_phantom : ()] Members[$($($member : $member_ty),*),*]);
+        fixture!(@impl_struct $name Hidden[$($hidden : $hidden_ty = $hidden_init,)*] Params[_phantom : ()] Members[$($($member : $member_ty),*),*]);
 
         impl<'param> ::galvanic_test::TestFixture<'param, (), $ret_ty> for $name<'param> {
             fn new(_phantom: &'param ()) -> Self {
                 Self {
+                    $($hidden: $hidden_init,)*
                     _phantom,
                     $($($member: None),*),*
                 }
             }
-            fn parameters() -> Option<Box<Iterator<Item=()>>> {
+            fn parameters() -> Option<Box<dyn Iterator<Item=()>>> {
                 Some(Box::new(Some(()).into_iter()))
             }
-            fn setup(&mut $self_setup) -> ::galvanic_test::FixtureBinding<Self, $ret_ty> {
-                let value = $setup_body;
-                ::galvanic_test::FixtureBinding {
-                    val: value,
-                    params: $self_setup
-                }
-            }
-            $(fn tear_down(&$self_td) $tear_down_body)*
+            $(fn timeout() -> Option<::std::time::Duration> {
+                Some($timeout)
+            })?
+            fixture!(@setup_methods $name $self_setup $deps Error $error -> $ret_ty $setup_body);
+            $(fixture!(@tear_down_method $name $self_td $deps Error $error $tear_down_body);)*
+            fixture!(@resolve_dependencies $deps);
         }
 
+        fixture!(@impl_value $name Deps$deps -> $ret_ty);
         fixture!(@impl_drop $name);
     };
 
-    ( $name:ident ($($param:ident : $param_ty:ty),+) -> $ret_ty:ty {
+    ( @define $name:ident Hidden[$($hidden:ident : $hidden_ty:ty = $hidden_init:expr,)*] Deps $deps:tt Params[$($param:ident : $param_ty:ty,)+]
+          Error $error:tt -> $ret_ty:ty {
+          $(timeout($timeout:expr))?
           $(members { $($member:ident : Option<$member_ty:ty>),* })*
           $(params $params_body:block)*
           setup(& mut $self_setup:ident) $setup_body:block
           $(tear_down(&$self_td:ident) $tear_down_body:block)*
       }
     ) => {
-        fixture!(@impl_struct $name Params[$($param : $param_ty),*] Members[$($($member : $member_ty),*),*]);
+        fixture!(@impl_struct $name Hidden[$($hidden : $hidden_ty = $hidden_init,)*] Params[$($param : $param_ty),*] Members[$($($member : $member_ty),*),*]);
 
         impl<'param> ::galvanic_test::TestFixture<'param, ($($param_ty),*), $ret_ty> for $name<'param> {
-            fixture!(@new_method Params[$($param : $param_ty),*] Members[$($($member),*),*]);
-            fn parameters() -> Option<Box<Iterator<Item=($($param_ty),*)>>> {
-                (None as Option<Box<Iterator<Item=($($param_ty),*)>>>)
-                $(; Some(Box::new($params_body)))*
+            fixture!(@new_method Hidden[$($hidden = $hidden_init,)*] Params[$($param : $param_ty),*] Members[$($($member),*),*]);
+            fn parameters() -> Option<Box<dyn Iterator<Item=($($param_ty),*)>>> {
+                Self::cases().map(|cases| {
+                    Box::new(cases.map(::galvanic_test::Case::into_params)) as Box<dyn Iterator<Item=($($param_ty),*)>>
+                })
             }
-            fn setup(&mut $self_setup) -> ::galvanic_test::FixtureBinding<Self, $ret_ty> {
-                let value = $setup_body;
-                ::galvanic_test::FixtureBinding {
-                    val: value,
-                    params: $self_setup
-                }
+            fn cases() -> Option<Box<dyn Iterator<Item=::galvanic_test::Case<($($param_ty),*)>>>> {
+                (None as Option<Box<dyn Iterator<Item=::galvanic_test::Case<($($param_ty),*)>>>>)
+                $(; Some(Box::new($params_body)))*
             }
-            $(fn tear_down(&$self_td) $tear_down_body)*
+            $(fn timeout() -> Option<::std::time::Duration> {
+                Some($timeout)
+            })?
+            fixture!(@setup_methods $name $self_setup $deps Error $error -> $ret_ty $setup_body);
+            $(fixture!(@tear_down_method $name $self_td $deps Error $error $tear_down_body);)*
+            fixture!(@resolve_dependencies $deps);
         }
 
+        fixture!(@impl_value $name Deps$deps -> $ret_ty);
         fixture!(@impl_drop $name);
     };
+
+    // the body of the fixture does not match any of the arms above, the most common mistakes are explained
+    ( @define $name:ident Hidden $hidden:tt Deps $deps:tt Params $params:tt Error $error:tt -> $ret_ty:ty { $($body:tt)* } ) => {
+        fixture!(@diagnose $name $params [start] $($body)*);
+    };
+
+    // walks through the entries of the fixture's body, the phase is the kind of the last entry
+    ( @diagnose $name:ident $params:tt [setup] ) => {
+        ::galvanic_test::__dsl_error!([$name] "the fixture `{}` is malformed");
+    };
+    ( @diagnose $name:ident $params:tt [$phase:ident] ) => {
+        ::galvanic_test::__dsl_error!([$name] "the fixture `{}` requires a `setup(&mut self) { ... }` block");
+    };
+    // the first token of the entry is repeated in brackets so that errors point at the user's token
+    ( @diagnose $name:ident $params:tt $phase:tt $entry:tt $($remainder:tt)* ) => {
+        fixture!(@diagnose_entry [$entry] $name $params $phase $entry $($remainder)*);
+    };
+
+    ( @diagnose_entry $at:tt $name:ident $params:tt [start] timeout ( $($timeout:tt)* ) $($remainder:tt)* ) => {
+        fixture!(@diagnose $name $params [members] $($remainder)*);
+    };
+    ( @diagnose_entry $at:tt $name:ident $params:tt [$phase:ident] timeout $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!($at "`timeout(...)` must be the first entry of a fixture");
+    };
+    ( @diagnose_entry $at:tt $name:ident $params:tt [params] members $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!($at "`members` must be declared before the `params` of a fixture");
+    };
+    ( @diagnose_entry $at:tt $name:ident $params:tt [setup] members $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!($at "`members` must be declared before the `setup` of a fixture");
+    };
+    ( @diagnose_entry $at:tt $name:ident $params:tt [$phase:ident] members { $($members:tt)* } $($remainder:tt)* ) => {
+        fixture!(@diagnose_members [$name $params [members] $($remainder)*] $($members)*);
+    };
+    ( @diagnose_entry $at:tt $name:ident [] [$phase:ident] params $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!($at
+            "`params` require typed arguments of the fixture, e.g., `fixture!(name(value: u32) -> ...)`");
+    };
+    ( @diagnose_entry $at:tt $name:ident $params:tt [setup] params $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!($at "`params` must be declared before the `setup` of a fixture");
+    };
+    ( @diagnose_entry $at:tt $name:ident $params:tt [$phase:ident] params { $($params_body:tt)* } $($remainder:tt)* ) => {
+        fixture!(@diagnose $name $params [params] $($remainder)*);
+    };
+    ( @diagnose_entry $at:tt $name:ident $params:tt [$phase:ident] params $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!($at
+            "`params` must be followed by a block returning an iterator or by `labelled`, `generate(...)`, `from_file(...)` or `glob(...)`");
+    };
+    ( @diagnose_entry $at:tt $name:ident $params:tt [setup] setup $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!($at "a fixture must have a single `setup`");
+    };
+    ( @diagnose_entry $at:tt $name:ident $params:tt [$phase:ident] setup ( & mut $self_:ident ) { $($body:tt)* } $($remainder:tt)* ) => {
+        fixture!(@diagnose $name $params [setup] $($remainder)*);
+    };
+    ( @diagnose_entry [$setup:tt] $name:ident $params:tt [$phase:ident] setup $receiver:tt $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!([$setup $receiver] "the setup of a fixture must be declared as `setup(&mut self) { ... }`");
+    };
+    ( @diagnose_entry $at:tt $name:ident $params:tt [setup] tear_down ( & $self_:ident ) { $($body:tt)* } $($remainder:tt)* ) => {
+        fixture!(@diagnose $name $params [setup] $($remainder)*);
+    };
+    ( @diagnose_entry [$tear_down:tt] $name:ident $params:tt [setup] tear_down $receiver:tt $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!([$tear_down $receiver] "the tear down of a fixture must be declared as `tear_down(&self) { ... }`");
+    };
+    ( @diagnose_entry $at:tt $name:ident $params:tt [$phase:ident] tear_down $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!($at "`tear_down` must be declared after the `setup` of a fixture");
+    };
+    ( @diagnose_entry $at:tt $name:ident $params:tt [$phase:ident] $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!($at
+            "unexpected `{}` in fixture, expected `timeout`, `members`, `params`, `setup` or `tear_down`");
+    };
+
+    ( @diagnose_members [$($diagnose:tt)*] $member:ident : Option<$member_ty:ty> $(, $($members:tt)*)? ) => {
+        fixture!(@diagnose_members [$($diagnose)*] $($($members)*)?);
+    };
+    ( @diagnose_members [$($diagnose:tt)*] ) => {
+        fixture!(@diagnose $($diagnose)*);
+    };
+    ( @diagnose_members $diagnose:tt $member:ident : $member_ty:ty $(, $($members:tt)*)? ) => {
+        ::galvanic_test::__dsl_error!([$member]
+            "fixture members must be declared as `name: Option<T>` as they are unset until the setup of the fixture");
+    };
+    ( @diagnose_members $diagnose:tt $($members:tt)+ ) => {
+        ::galvanic_test::__dsl_error!([$($members)*] "fixture members must be declared as `name: Option<T>`, separated by commas");
+    };
+
+    ( @normalize [$($prefix:tt)*] [$($done:tt)*] ) => {
+        fixture!($($prefix)* { $($done)* });
+    };
+    // the first token of the entry is repeated in brackets so that the rewritten entry keeps the user's keyword,
+    // errors about the entry then point at the keyword
+    ( @normalize $prefix:tt $done:tt $entry:tt $($remainder:tt)* ) => {
+        fixture!(@normalize_entry [$entry] $prefix $done $entry $($remainder)*);
+    };
+
+    // rewrites the `params` block to produce the fixture's cases
+    // cases may be annotated with their expected outcome, e.g., `#[xfail]`, `#[should_panic("message")]` or `#[skip("reason")]`
+    ( @normalize_entry [$params:tt] [$($prefix:tt)*] [$($done:tt)*]
+      params { $($(#[$outcome:ident $(($($outcome_arg:expr),*))?])* case $label:expr => $case:expr),+ $(,)? } $($remainder:tt)*
+    ) => {
+        fixture!(@normalize [$($prefix)*] [$($done)* $params {
+            ::std::vec![$(
+                ::galvanic_test::Case::labelled($label, $case) $(.$outcome($($($outcome_arg),*)?))*
+            ),+].into_iter()
+        }] $($remainder)*);
+    };
+    // generates the cases from a strategy whose items can be used without importing them
+    ( @normalize_entry [$params:tt] [@args $name:ident $($prefix:tt)*] [$($done:tt)*]
+      params generate($($key:ident = $value:expr),* $(,)?) { $($strategy:tt)* } $($remainder:tt)*
+    ) => {
+        fixture!(@normalize [@args $name $($prefix)*] [$($done)* $params {
+            ::galvanic_test::generate::Generator::new(_galvanic__stringify!($name))
+                $(.$key($value))*
+                .cases({
+                    #[allow(unused_imports)]
+                    use ::galvanic_test::generate::*;
+                    $($strategy)*
+                })
+        }] $($remainder)*);
+    };
+    // loads the cases from a data file relative to the manifest of the tested crate
+    ( @normalize_entry [$params:tt] [$($prefix:tt)*] [$($done:tt)*] params from_file($path:expr $(,)?) $($remainder:tt)* ) => {
+        fixture!(@normalize [$($prefix)*] [$($done)* $params {
+            ::galvanic_test::cases_from_file(::std::env!("CARGO_MANIFEST_DIR"), $path).into_iter()
+        }] $($remainder)*);
+    };
+    // creates a case for each path matching the glob pattern relative to the manifest of the tested crate
+    ( @normalize_entry [$params:tt] [$($prefix:tt)*] [$($done:tt)*] params glob($pattern:expr $(,)?) $($remainder:tt)* ) => {
+        fixture!(@normalize [$($prefix)*] [$($done)* $params {
+            ::galvanic_test::cases_from_glob(::std::env!("CARGO_MANIFEST_DIR"), $pattern).into_iter()
+        }] $($remainder)*);
+    };
+    ( @normalize_entry [$params:tt] [$($prefix:tt)*] [$($done:tt)*] params labelled { $($params_body:tt)* } $($remainder:tt)* ) => {
+        fixture!(@normalize [$($prefix)*] [$($done)* $params {
+            ::std::iter::Iterator::map({ $($params_body)* }, |(label, params)| ::galvanic_test::Case::labelled(label, params))
+        }] $($remainder)*);
+    };
+    ( @normalize_entry [$params:tt] [$($prefix:tt)*] [$($done:tt)*] params { $($params_body:tt)* } $($remainder:tt)* ) => {
+        fixture!(@normalize [$($prefix)*] [$($done)* $params {
+            ::std::iter::Iterator::map({ $($params_body)* }, ::galvanic_test::Case::new)
+        }] $($remainder)*);
+    };
+    // rewrites `async setup` and `async tear_down` blocks to run on the async runtime
+    ( @normalize_entry $async:tt $prefix:tt $done:tt async $entry:tt $($remainder:tt)* ) => {
+        fixture!(@normalize_async $async [$entry] $prefix $done $entry $($remainder)*);
+    };
+    ( @normalize_entry $at:tt [$($prefix:tt)*] [$($done:tt)*] $token:tt $($remainder:tt)* ) => {
+        fixture!(@normalize [$($prefix)*] [$($done)* $token] $($remainder)*);
+    };
+
+    ( @normalize_async $async:tt [$setup:tt] [$($prefix:tt)*] [$($done:tt)*]
+      setup $self_arg:tt { $($setup_body:tt)* } $($remainder:tt)*
+    ) => {
+        fixture!(@normalize [$($prefix)*] [$($done)* $setup $self_arg {
+            ::galvanic_test::block_on(async { $($setup_body)* })
+        }] $($remainder)*);
+    };
+    ( @normalize_async $async:tt [$tear_down:tt] [$($prefix:tt)*] [$($done:tt)*]
+      tear_down $self_arg:tt { $($tear_down_body:tt)* } $($remainder:tt)*
+    ) => {
+        fixture!(@normalize [$($prefix)*] [$($done)* $tear_down $self_arg {
+            ::galvanic_test::block_on(async { $($tear_down_body)* })
+        }] $($remainder)*);
+    };
+    ( @normalize_async [$async:tt] $at:tt [$($prefix:tt)*] [$($done:tt)*] $($remainder:tt)* ) => {
+        fixture!(@normalize [$($prefix)*] [$($done)* $async] $($remainder)*);
+    };
+
+    ( $name:ident ( $($args:tt)* ) -> Result<$ret_ty:ty, $err_ty:ty> { $($body:tt)* } ) => {
+        fixture!(@normalize [@args $name [] [] ( $($args)* ) Error[$err_ty] -> $ret_ty] [] $($body)*);
+    };
+
+    ( $name:ident ( $($args:tt)* ) -> $ret_ty:ty { $($body:tt)* } ) => {
+        fixture!(@normalize [@args $name [] [] ( $($args)* ) Error[] -> $ret_ty] [] $($body)*);
+    };
+    ( $name:ident $args:tt { $($body:tt)* } ) => {
+        ::galvanic_test::__dsl_error!([$name $args]
+            "a fixture must declare the type of the value it sets up, e.g., `{} -> Type`");
+    };
 }
 
 #[macro_export(local_inner_macros)]
 macro_rules! test {
-    ( @parameters | $body:block $test_case_failed:ident ) => { $body };
-
-    ( @parameters | $body:block $test_case_failed:ident $(($fixture_obj:ident, $params:expr, $fixture:ident))+) => {
-        let mut described_parameters = String::from("Test panicked before all fixtures have been assigned.");
-        let result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {
-            let mut described_params = Vec::new();
-            $(
-                let params = &$params;
-                let mut $fixture_obj = $fixture::new(params);
-                described_params.push(_galvanic__format!("{:?}", $fixture_obj));
-                let mut $fixture = $fixture_obj.setup();
-                noop(&$fixture);
-            )*
-            described_parameters = described_params.join(", ");
-            $body
-        }));
-        if result.is_err() {
-            _galvanic__println!("The above error occured with the following parameterisation of the test case:\n    {}\n",
-                     described_parameters);
-            $test_case_failed.set(true);
+    ( @parameters | -> $ret_ty:ty $body:block $report:ident $mode:tt $($fixtures:tt)* ) => {
+        test!(@case Ret[$ret_ty] $body $report $mode $($fixtures)*);
+    };
+    ( @parameters | $body:block $report:ident $mode:tt $($fixtures:tt)* ) => {
+        test!(@case Ret[] $body $report $mode $($fixtures)*);
+    };
+
+    ( @body Ret[] $body:block ) => {
+        (|| $body)();
+    };
+    // an error returned by the test is a failure of the test case
+    ( @body Ret[$ret_ty:ty] $body:block ) => {
+        if let Err(error) = (|| -> $ret_ty { $body })() {
+            return Err(::galvanic_test::CaseError::Failed(_galvanic__format!("{:?}", error)));
+        }
+    };
+
+    ( @case Ret $ret:tt $body:block $report:ident [] $(($fixture_obj:ident, $cases:ident, $fixture:ident))+) => {
+        $($report.generated_with($cases.iter().filter_map(::galvanic_test::Case::seed));)+
+        let combinations = $report.combinations(&[$($cases.len()),+]);
+        while let Some(combination) = $report.next_combination(&combinations) {
+            test!(@combination Ret $ret $body $report combination $(($fixture_obj, $cases, $fixture))+);
+        }
+    };
+    // the parameterisations are executed on a thread pool, requiring the fixtures' parameters to be `Sync`
+    ( @case Ret $ret:tt $body:block $report:ident [parallel($($threads:tt)*)] $(($fixture_obj:ident, $cases:ident, $fixture:ident))+) => {
+        $($report.generated_with($cases.iter().filter_map(::galvanic_test::Case::seed));)+
+        let combinations = $report.combinations(&[$($cases.len()),+]);
+        ::galvanic_test::run_parallel(test!(@threads $($threads)*), &$report, &combinations, |combination| {
+            test!(@combination Ret $ret $body $report combination $(($fixture_obj, $cases, $fixture))+);
+        });
+    };
+    ( @threads ) => { None };
+    ( @threads threads = $threads:expr ) => { Some($threads) };
+
+    // executes the parameterisation selected by a combination of the fixtures' cases
+    ( @combination Ret $ret:tt $body:block $report:ident $combination:ident $(($fixture_obj:ident, $cases:ident, $fixture:ident))+) => {
+        {
+            let mut indices = $combination.iter();
+            let expectation = ::galvanic_test::Expectation::of_combination(&[
+                $($cases[*indices.next().unwrap()].expectation()),+
+            ]);
+            // executes the test with the given cases of the fixtures, their types are erased to shrink them independently
+            let run_case = |fixture_cases: &[&::galvanic_test::AnyCase]| {
+                let mut described_parameters = String::from("Test panicked before all fixtures have been assigned.");
+                let mut fixture_cases = fixture_cases.iter();
+                let result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(
+                    || -> Result<(), ::galvanic_test::CaseError> {
+                        let mut described_params = Vec::new();
+                        // declared before the fixtures so that dependencies are torn down last
+                        let dependencies = ::galvanic_test::Dependencies::new();
+                        $(
+                            let fixture_case = ::galvanic_test::unerase(&$cases, *fixture_cases.next().unwrap());
+                            let mut $fixture_obj = $fixture::new(fixture_case.params());
+                            described_params.push(fixture_case.describe(&$fixture_obj));
+                        )*
+                        described_parameters = described_params.join(", ");
+                        if !$report.is_selected(&described_parameters) {
+                            return Ok(());
+                        }
+                        if let ::galvanic_test::Expectation::Skip(_) = expectation {
+                            return Ok(());
+                        }
+                        let _timed_case = $report.time_case(&described_parameters);
+                        let _snapshot_case = ::galvanic_test::SnapshotCase::enter(&described_parameters);
+
+                        // the dependencies are set up first, so an injected fixture which is a dependency of another
+                        // fixture is set up once and shared by the test, whatever the order of the fixtures
+                        $(
+                            $fixture_obj.resolve_dependencies(&dependencies)?;
+                        )*
+                        $(
+                            let injected = ::galvanic_test::InjectedDependency::<$fixture>::of(
+                                _galvanic__stringify!($fixture), &dependencies
+                            );
+                            // the fixture's binding is named after the fixture, which may be a struct deriving `TestFixture`
+                            #[allow(non_snake_case)]
+                            let mut $fixture = match injected {
+                                Some(ref injected) => {
+                                    use ::galvanic_test::{CloneInjectedValue as _, UncloneableInjectedValue as _};
+                                    $fixture_obj.discard();
+                                    ::galvanic_test::FixtureBinding { val: injected.value(), params: injected.fixture() }
+                                }
+                                None => $fixture_obj.try_setup()?,
+                            };
+                            noop(&$fixture);
+                        )*
+                        // the body is wrapped in a closure so that an early `return` leaves the test case only
+                        test!(@body Ret $ret $body);
+                        Ok(())
+                    }
+                ));
+                (described_parameters, result)
+            };
+            let mut indices = $combination.iter();
+            let fixture_cases = [$(&$cases[*indices.next().unwrap()] as &::galvanic_test::AnyCase),+];
+            // a failing case is executed again with fresh fixtures if the test should be retried
+            let mut attempt = 0;
+            loop {
+                let (described_parameters, result) = run_case(&fixture_cases);
+                match $report.record(result, &described_parameters, &expectation, attempt) {
+                    ::galvanic_test::Recorded::Done => break,
+                    ::galvanic_test::Recorded::Retry => attempt += 1,
+                    // a failing generated parameterisation is reported by the simplest parameterisation which still fails
+                    ::galvanic_test::Recorded::Failed(failure) => {
+                        let shrinkers = [$(::galvanic_test::shrinker(&$cases)),+];
+                        let minimal = ::galvanic_test::shrink(&fixture_cases, &shrinkers, |fixture_cases| {
+                            let (described_parameters, result) = run_case(fixture_cases);
+                            $report.failure(result, &described_parameters, &expectation)
+                                .map(|message| (described_parameters, message))
+                        });
+                        if let Some(minimal) = minimal {
+                            $report.shrunk(failure, minimal);
+                        }
+                        break;
+                    }
+                }
+            }
         }
     };
 
@@ -179,47 +765,278 @@ macro_rules! test {
         test!(@parameters $($remainder)*);
     };
 
+    // the cases of each fixture are collected before they are combined
     ( @parameters $fixture:ident ( $($expr:expr),* ) $($remainder:tt)+ ) => {
-        test!(@parameters $($remainder)* (fixture_obj, ($($expr),*), $fixture));
+        let cases = ::std::vec![::galvanic_test::Case::new(($($expr),*))];
+        test!(@parameters $($remainder)* (fixture_obj, cases, $fixture));
+    };
+
+    // selects a single case of the fixture by its index
+    ( @parameters $fixture:ident [ $index:expr ] $($remainder:tt)+ ) => {
+        let cases: Vec<_> = match $fixture::cases().and_then(|mut cases| cases.nth($index)) {
+            Some(case) => ::std::vec![case],
+            None => _galvanic__panic!("The fixture `{}` has no case with the index {}.",
+                                      _galvanic__stringify!($fixture), $index)
+        };
+        test!(@parameters $($remainder)* (fixture_obj, cases, $fixture));
     };
 
     ( @parameters $fixture:ident $($remainder:tt)+ ) => {
-        match $fixture::parameters() {
-            Some(iterator) => {
-                for params in iterator {
-                    test!(@parameters $($remainder)* (fixture_obj, params, $fixture));
-                }
-            },
+        let cases: Vec<_> = match $fixture::cases() {
+            Some(cases) => cases.collect(),
             None => _galvanic__panic!(_galvanic__concat!(
                 "If a test fixture should be injected without supplying parameters, ",
                 "it either needs to have no arguments ",
                 "or a `params` block returning an iterator of parameter tuples ",
                 "must be given for the fixture."))
+        };
+        test!(@parameters $($remainder)* (fixture_obj, cases, $fixture));
+    };
+
+    // whether the test, one of its injected fixtures or one of their dependencies has a timeout
+    ( @has_timeout $options:tt Fixtures[$($fixture:ident)*] | $($body:tt)* ) => {
+        test!(@timeout_option $options) $(|| $fixture::has_timeout())*
+    };
+    ( @has_timeout $options:tt Fixtures[$($fixture:ident)*] , $($remainder:tt)* ) => {
+        test!(@has_timeout $options Fixtures[$($fixture)*] $($remainder)*)
+    };
+    ( @has_timeout $options:tt Fixtures[$($fixture:ident)*] $next:ident ( $($args:tt)* ) $($remainder:tt)* ) => {
+        test!(@has_timeout $options Fixtures[$($fixture)* $next] $($remainder)*)
+    };
+    ( @has_timeout $options:tt Fixtures[$($fixture:ident)*] $next:ident [ $($index:tt)* ] $($remainder:tt)* ) => {
+        test!(@has_timeout $options Fixtures[$($fixture)* $next] $($remainder)*)
+    };
+    ( @has_timeout $options:tt Fixtures[$($fixture:ident)*] $next:ident $($remainder:tt)* ) => {
+        test!(@has_timeout $options Fixtures[$($fixture)* $next] $($remainder)*)
+    };
+    ( @has_timeout [$($option:ident)*] $($args_and_body:tt)* ) => {
+        test!(@has_timeout [$($option)*] Fixtures[] $($args_and_body)*)
+    };
+    ( @timeout_option [] ) => { false };
+    ( @timeout_option [timeout $($option:ident)*] ) => { true };
+    ( @timeout_option [$skipped:ident $($option:ident)*] ) => { test!(@timeout_option [$($option)*]) };
+
+    // configures the report of a parameterised test according to the galvanic-specific attributes
+    ( @option $report:ident combine(product) ) => {
+        $report.combine(::galvanic_test::combinations::product);
+    };
+    ( @option $report:ident combine(zip) ) => {
+        $report.combine(::galvanic_test::combinations::zip);
+    };
+    ( @option $report:ident combine(pairwise) ) => {
+        $report.combine(::galvanic_test::combinations::pairwise);
+    };
+    ( @option $report:ident combine($combinator:path) ) => {
+        $report.combine($combinator);
+    };
+    ( @option $report:ident expect_panic($message:expr) ) => {
+        $report.expect_panic($message);
+    };
+    ( @option $report:ident retry($retries:expr) ) => {
+        $report.retry($retries);
+    };
+    ( @option $report:ident timeout($timeout:expr) ) => {
+        $report.timeout($timeout);
+    };
+    ( @option $report:ident skip_if($condition:expr) ) => {
+        if $condition {
+            $report.skip(_galvanic__format!("`{}` holds", _galvanic__stringify!($condition)));
         }
     };
 
-    ( $(#[$attr:meta])* $name:ident | $($args_and_body:tt)* ) => {
+    // a test without fixtures, e.g., of a test suite, has a single case like a test declared without `|`
+    ( @with $prelude:tt $opts:tt $(#[$attr:meta])* $name:ident | | $body:block ) => {
+        test!(@with $prelude $opts $(#[$attr])* $name $body);
+    };
+    // its error is reported by a panic so that the test may expect one
+    ( @with $prelude:tt $opts:tt $(#[$attr:meta])* $name:ident | | -> $ret_ty:ty $body:block ) => {
+        test!(@with $prelude $opts $(#[$attr])* $name {
+            if let Err(error) = (|| -> $ret_ty { $body })() {
+                _galvanic__panic!("The test returned an error: {:?}", error);
+            }
+        });
+    };
+    // `parallel` is always the first option of a test, see `@attrs`
+    ( @with $prelude:tt [parallel $parallel_args:tt $($opts:tt)*] $(#[$attr:meta])* $name:ident | $($args_and_body:tt)* ) => {
+        test!(@parameterised [parallel $parallel_args] $prelude [$($opts)*] $(#[$attr])* $name | $($args_and_body)*);
+    };
+    ( @with $prelude:tt $opts:tt $(#[$attr:meta])* $name:ident | $($args_and_body:tt)* ) => {
+        test!(@parameterised [] $prelude $opts $(#[$attr])* $name | $($args_and_body)*);
+    };
+    ( @parameterised $mode:tt [$($prelude:stmt;)*] [$($option:ident $option_args:tt)*] $(#[$attr:meta])* $name:ident | $($args_and_body:tt)* ) => {
         #[test]
         $(#[$attr])*
         fn $name() {
-            #[allow(dead_code)]
-            fn noop<F, R>(_: &::galvanic_test::FixtureBinding<F,R>) { }
-            // Cell is a workaround for #![allow(unused_mut)] which would affect the whole fn
-            let test_case_failed = ::std::cell::Cell::new(false);
-            test!(@parameters $($args_and_body)* test_case_failed);
-            if test_case_failed.get() {
-                _galvanic__panic!("Some parameterised test cases failed");
+            $($prelude;)*
+            let snapshots = test!(@snapshots $name);
+            #[allow(unused_mut)]
+            let mut report = ::galvanic_test::TestReport::new();
+            $(test!(@option report $option $option_args);)*
+            let report = ::std::sync::Arc::new(report);
+            let test = {
+                let report = report.clone();
+                move || {
+                    #[allow(dead_code)]
+                    fn noop<F, R>(_: &::galvanic_test::FixtureBinding<F,R>) { }
+                    test!(@parameters $($args_and_body)* report $mode);
+                }
+            };
+            // a test with timeouts runs on a worker thread so that they can be enforced
+            if test!(@has_timeout [$($option)*] $($args_and_body)*) {
+                ::galvanic_test::supervise_cases(&report, test);
+            } else {
+                test();
+            }
+            let complete = report.is_complete();
+            report.finish();
+            snapshots.finish(complete);
+        }
+    };
+
+    // a test without fixtures has a single case so an expected panic is checked by `#[should_panic]`
+    ( @with $prelude:tt [expect_panic($message:expr) $($opts:tt)*] $(#[$attr:meta])* $name:ident -> $ret_ty:ty $body:block ) => {
+        test!(@with $prelude [$($opts)*] $(#[$attr])* #[should_panic(expected = $message)] $name {
+            if let Err(error) = (|| -> $ret_ty { $body })() {
+                _galvanic__panic!("The test returned an error: {:?}", error);
+            }
+        });
+    };
+    ( @with $prelude:tt [expect_panic($message:expr) $($opts:tt)*] $(#[$attr:meta])* $name:ident $body:block ) => {
+        test!(@with $prelude [$($opts)*] $(#[$attr])* #[should_panic(expected = $message)] $name $body);
+    };
+
+    ( @with $prelude:tt [retry($retries:expr) $($opts:tt)*] $(#[$attr:meta])* $name:ident -> $ret_ty:ty $body:block ) => {
+        test!(@with $prelude [$($opts)*] $(#[$attr])* $name {
+            ::galvanic_test::retried($retries, || {
+                if let Err(error) = (|| -> $ret_ty { $body })() {
+                    _galvanic__panic!("The test returned an error: {:?}", error);
+                }
+            });
+        });
+    };
+    ( @with $prelude:tt [retry($retries:expr) $($opts:tt)*] $(#[$attr:meta])* $name:ident $body:block ) => {
+        test!(@with $prelude [$($opts)*] $(#[$attr])* $name {
+            ::galvanic_test::retried($retries, || $body);
+        });
+    };
+
+    ( @with $prelude:tt [timeout($timeout:expr) $($opts:tt)*] $(#[$attr:meta])* $name:ident -> $ret_ty:ty $body:block ) => {
+        test!(@with $prelude [$($opts)*] $(#[$attr])* $name {
+            ::galvanic_test::supervise(move || {
+                let _timed_section = ::galvanic_test::TimedSection::enter(Some($timeout), || String::from("the test"));
+                if let Some(Err(error)) = ::galvanic_test::skippable(|| -> $ret_ty { $body }) {
+                    _galvanic__panic!("The test returned an error: {:?}", error);
+                }
+            });
+        });
+    };
+    ( @with $prelude:tt [timeout($timeout:expr) $($opts:tt)*] $(#[$attr:meta])* $name:ident $body:block ) => {
+        test!(@with $prelude [$($opts)*] $(#[$attr])* $name {
+            ::galvanic_test::supervise(move || {
+                let _timed_section = ::galvanic_test::TimedSection::enter(Some($timeout), || String::from("the test"));
+                ::galvanic_test::skippable(|| $body);
+            });
+        });
+    };
+
+    ( @with $prelude:tt [skip_if($condition:expr) $($opts:tt)*] $(#[$attr:meta])* $name:ident $(-> $ret_ty:ty)? $body:block ) => {
+        test!(@with $prelude [$($opts)*] $(#[$attr])* $name $(-> $ret_ty)? {
+            if $condition {
+                skip!("`{}` holds", _galvanic__stringify!($condition));
+            }
+            $body
+        });
+    };
+
+    // other options only apply to parameterised tests
+    ( @with $prelude:tt [$option:ident $option_args:tt $($opts:tt)*] $(#[$attr:meta])* $name:ident $(-> $ret_ty:ty)? $body:block ) => {
+        test!(@with $prelude [$($opts)*] $(#[$attr])* $name $(-> $ret_ty)? $body);
+    };
+
+    ( @with [$($prelude:stmt;)*] [] $(#[$attr:meta])* $name:ident -> $ret_ty:ty $body:block ) => {
+        #[test]
+        $(#[$attr])*
+        fn $name() -> $ret_ty {
+            $($prelude;)*
+            let snapshots = test!(@snapshots $name);
+            match ::galvanic_test::skippable(|| -> $ret_ty { $body }) {
+                Some(result) => {
+                    snapshots.finish(result.is_ok());
+                    result
+                }
+                None => Ok(()),
             }
         }
     };
 
-    ( $(#[$attr:meta])* $name:ident $body:block ) => {
+    ( @with [$($prelude:stmt;)*] [] $(#[$attr:meta])* $name:ident $body:block ) => {
         #[test]
         $(#[$attr])*
         fn $name() {
-            $body
+            $($prelude;)*
+            let snapshots = test!(@snapshots $name);
+            let complete = ::galvanic_test::skippable(|| $body).is_some();
+            snapshots.finish(complete);
         }
     };
+
+    // the snapshots of a test are stored in the tested crate
+    ( @snapshots $name:ident ) => {
+        ::galvanic_test::SnapshotScope::enter(
+            ::std::env!("CARGO_MANIFEST_DIR"), ::std::module_path!(), _galvanic__stringify!($name)
+        )
+    };
+
+    // attributes specific to galvanic-test are separated from the test's other attributes
+    ( @attrs $prelude:tt [$($opts:tt)*] $attrs:tt #[combine $strategy:tt] $($remainder:tt)* ) => {
+        test!(@attrs $prelude [$($opts)* combine $strategy] $attrs $($remainder)*);
+    };
+    ( @attrs $prelude:tt [$($opts:tt)*] $attrs:tt #[expect_panic $message:tt] $($remainder:tt)* ) => {
+        test!(@attrs $prelude [$($opts)* expect_panic $message] $attrs $($remainder)*);
+    };
+    ( @attrs $prelude:tt [$($opts:tt)*] $attrs:tt #[expect_panic] $($remainder:tt)* ) => {
+        test!(@attrs $prelude [$($opts)* expect_panic("")] $attrs $($remainder)*);
+    };
+    ( @attrs $prelude:tt [$($opts:tt)*] $attrs:tt #[retry $retries:tt] $($remainder:tt)* ) => {
+        test!(@attrs $prelude [$($opts)* retry $retries] $attrs $($remainder)*);
+    };
+    ( @attrs $prelude:tt [$($opts:tt)*] $attrs:tt #[timeout $timeout:tt] $($remainder:tt)* ) => {
+        test!(@attrs $prelude [$($opts)* timeout $timeout] $attrs $($remainder)*);
+    };
+    ( @attrs $prelude:tt [$($opts:tt)*] $attrs:tt #[skip_if $condition:tt] $($remainder:tt)* ) => {
+        test!(@attrs $prelude [$($opts)* skip_if $condition] $attrs $($remainder)*);
+    };
+    ( @attrs $prelude:tt [$($opts:tt)*] $attrs:tt #[parallel $threads:tt] $($remainder:tt)* ) => {
+        test!(@attrs $prelude [parallel $threads $($opts)*] $attrs $($remainder)*);
+    };
+    ( @attrs $prelude:tt [$($opts:tt)*] $attrs:tt #[parallel] $($remainder:tt)* ) => {
+        test!(@attrs $prelude [parallel() $($opts)*] $attrs $($remainder)*);
+    };
+    ( @attrs $prelude:tt $opts:tt [$($attrs:tt)*] #[$($attr:tt)*] $($remainder:tt)* ) => {
+        test!(@attrs $prelude $opts [$($attrs)* #[$($attr)*]] $($remainder)*);
+    };
+    ( @attrs $prelude:tt $opts:tt $attrs:tt async $name:ident $($remainder:tt)+ ) => {
+        test!(@async [@attrs $prelude $opts $attrs $name] $($remainder)*);
+    };
+    ( @attrs $prelude:tt $opts:tt [$($attrs:tt)*] $name:ident $($remainder:tt)+ ) => {
+        test!(@with $prelude $opts $($attrs)* $name $($remainder)*);
+    };
+
+    // runs the body of an async test, i.e., the last token, on the async runtime
+    ( @async [$($done:tt)*] { $($body:tt)* } ) => {
+        test!($($done)* { ::galvanic_test::block_on(async { $($body)* }) });
+    };
+    ( @async [$($done:tt)*] $token:tt $($remainder:tt)+ ) => {
+        test!(@async [$($done)* $token] $($remainder)*);
+    };
+
+    ( $(#[$($attr:tt)*])* async $name:ident $($remainder:tt)+ ) => {
+        test!(@attrs [] [] [] $(#[$($attr)*])* async $name $($remainder)*);
+    };
+
+    ( $(#[$($attr:tt)*])* $name:ident $($remainder:tt)+ ) => {
+        test!(@attrs [] [] [] $(#[$($attr)*])* $name $($remainder)*);
+    };
 }
 
 #[macro_export(local_inner_macros)]
@@ -230,7 +1047,7 @@ macro_rules! test_suite {
         #[cfg(test)]
         mod $name {
             #[allow(unused_imports)] use ::galvanic_test::TestFixture;
-            galvanic_test::__test_suite_int!(@int $($remainder)*);
+            galvanic_test::__test_suite_int!(@int [] [] $($remainder)*);
         }
     };
 
@@ -239,7 +1056,7 @@ macro_rules! test_suite {
         #[cfg(test)]
         mod __galvanic_test {
             #[allow(unused_imports)] use ::galvanic_test::TestFixture;
-            galvanic_test::__test_suite_int!(@int $($remainder)*);
+            galvanic_test::__test_suite_int!(@int [] [] $($remainder)*);
         }
     };
 }
@@ -256,7 +1073,7 @@ macro_rules! test_suite {
             #[use_mocks]
             mod with_mocks {
                 #[allow(unused_imports)] use ::galvanic_test::TestFixture;
-                galvanic_test::__test_suite_int!(@int $($remainder)*);
+                galvanic_test::__test_suite_int!(@int [] [] $($remainder)*);
             }
         }
     };
@@ -270,7 +1087,7 @@ macro_rules! test_suite {
             #[use_mocks]
             mod with_mocks {
                 #[allow(unused_imports)] use ::galvanic_test::TestFixture;
-                galvanic_test::__test_suite_int!(@int $($remainder)*);
+                galvanic_test::__test_suite_int!(@int [] [] $($remainder)*);
             }
         }
     };
@@ -278,42 +1095,168 @@ macro_rules! test_suite {
 
 #[macro_export(local_inner_macros)]
 macro_rules! __test_suite_int {
-    // internal: fixture in test_suite
-    ( @int $(#[$attr:meta])* fixture $name:ident ($($param:ident : $param_ty:ty),*) -> $ret_ty:ty {
-          $(members { $($member:ident : Option<$member_ty:ty>),* })*
-          $(params $params_body:block)*
-          setup(& mut $self_setup:ident) $setup_body:block
-          $(tear_down(&$self_td:ident) $tear_down_body:block)*
+    // internal: fixture shared by all tests of the test binary
+    ( @int $tests:tt $fixtures:tt scope session fixture $name:ident () -> Result<$ret_ty:ty, $err_ty:ty> {
+          $($body:tt)*
       } $($remainder:tt)*
     ) => {
-        fixture!( $(#[$attr])* $name ($($param : $param_ty),*) -> $ret_ty {
-              $(members { $($member : Option<$member_ty>),* })*
-              $(params $params_body)*
-              setup(& mut $self_setup) $setup_body
-              $(tear_down(&$self_td) $tear_down_body)*
-        });
-        galvanic_test::__test_suite_int!(@int $($remainder)*);
+        fixture!(scope session $name () -> Result<$ret_ty, $err_ty> { $($body)* });
+        galvanic_test::__test_suite_int!(@int $tests $fixtures $($remainder)*);
+    };
+    ( @int $tests:tt $fixtures:tt scope session fixture $name:ident () -> $ret_ty:ty {
+          $($body:tt)*
+      } $($remainder:tt)*
+    ) => {
+        fixture!(scope session $name () -> $ret_ty { $($body)* });
+        galvanic_test::__test_suite_int!(@int $tests $fixtures $($remainder)*);
+    };
+
+    // internal: fixture shared by all tests of the test suite
+    ( @int $tests:tt $fixtures:tt scope module fixture $($remainder:tt)*
+    ) => {
+        galvanic_test::__test_suite_int!(@int $tests $fixtures scope suite fixture $($remainder)*);
+    };
+    ( @int $tests:tt $fixtures:tt scope suite fixture $name:ident () -> Result<$ret_ty:ty, $err_ty:ty> {
+          $($body:tt)*
+      } $($remainder:tt)*
+    ) => {
+        fixture!(@shared [Some(&__GALVANIC_TEST_SUITE)] $name Value[$ret_ty] Decl[Result<$ret_ty, $err_ty>] { $($body)* });
+        galvanic_test::__test_suite_int!(@int $tests $fixtures $($remainder)*);
+    };
+    ( @int $tests:tt $fixtures:tt scope suite fixture $name:ident () -> $ret_ty:ty {
+          $($body:tt)*
+      } $($remainder:tt)*
+    ) => {
+        fixture!(@shared [Some(&__GALVANIC_TEST_SUITE)] $name Value[$ret_ty] Decl[$ret_ty] { $($body)* });
+        galvanic_test::__test_suite_int!(@int $tests $fixtures $($remainder)*);
+    };
+    ( @int $tests:tt $fixtures:tt scope $scope:ident fixture $name:ident ( $($args:tt)+ ) $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!([$($args)*] "a shared fixture is set up once and cannot have arguments");
+    };
+    ( @int $tests:tt $fixtures:tt scope $scope:ident fixture $name:ident $args:tt { $($body:tt)* } $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!([$name $args] "a fixture must declare the type of the value it sets up, e.g., `{} -> Type`");
+    };
+    ( @int $tests:tt $fixtures:tt scope $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!([$($remainder)*]
+            "a shared fixture must be declared as `scope session fixture name() -> Type { ... }` or with `scope suite`");
     };
 
-    // internal: test in test_suite
-    ( @int $(#[$attr:meta])* test $name:ident ( $($fixture:ident $(($($expr:expr),*))*),* )
-            $body:block
+    // internal: fixture in test_suite, its definition is kept for expanding the cases of `#[per_case]` tests
+    ( @int $tests:tt [$($fixtures:tt)*] $(#[$attr:meta])* fixture $name:ident ( $($args:tt)* ) -> Result<$ret_ty:ty, $err_ty:ty> {
+          $($body:tt)*
+      } $($remainder:tt)*
+    ) => {
+        fixture!( $(#[$attr])* $name ( $($args)* ) -> Result<$ret_ty, $err_ty> { $($body)* });
+        galvanic_test::__test_suite_int!(@int $tests [$($fixtures)* $name ( $($args)* ) { $($body)* }] $($remainder)*);
+    };
+    ( @int $tests:tt [$($fixtures:tt)*] $(#[$attr:meta])* fixture $name:ident ( $($args:tt)* ) -> $ret_ty:ty {
+          $($body:tt)*
+      } $($remainder:tt)*
+    ) => {
+        fixture!( $(#[$attr])* $name ( $($args)* ) -> $ret_ty { $($body)* });
+        galvanic_test::__test_suite_int!(@int $tests [$($fixtures)* $name ( $($args)* ) { $($body)* }] $($remainder)*);
+    };
+    ( @int $tests:tt $fixtures:tt $(#[$attr:meta])* fixture $name:ident $args:tt { $($body:tt)* } $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!([$name $args]
+            "a fixture must declare the type of the value it sets up, e.g., `{} -> Type`");
+    };
+
+    // internal: test in test_suite, attributes specific to galvanic-test are separated from the others
+    ( @int $tests:tt $fixtures:tt $(#[$($attr:tt)*])* test $($remainder:tt)* ) => {
+        galvanic_test::__test_suite_int!(@test_attrs $tests $fixtures [] [] $(#[$($attr)*])* test $($remainder)*);
+    };
+    ( @int $tests:tt $fixtures:tt $(#[$($attr:tt)*])* async test $($remainder:tt)* ) => {
+        galvanic_test::__test_suite_int!(@test_attrs $tests $fixtures [] [] $(#[$($attr)*])* async test $($remainder)*);
+    };
+
+    ( @test_attrs $tests:tt $fixtures:tt [$($opts:tt)*] [$($attrs:tt)*] #[per_case] $($remainder:tt)* ) => {
+        galvanic_test::__test_suite_int!(@test_attrs $tests $fixtures [$($opts)* per_case] [$($attrs)*] $($remainder)*);
+    };
+    ( @test_attrs $tests:tt $fixtures:tt $opts:tt [$($attrs:tt)*] #[$($attr:tt)*] $($remainder:tt)* ) => {
+        galvanic_test::__test_suite_int!(@test_attrs $tests $fixtures $opts [$($attrs)* #[$($attr)*]] $($remainder)*);
+    };
+    ( @test_attrs $tests:tt $fixtures:tt $opts:tt $attrs:tt async test $($remainder:tt)* ) => {
+        galvanic_test::__test_suite_int!(@async_test $tests $fixtures $opts $attrs [test] $($remainder)*);
+    };
+    ( @test_attrs $tests:tt $fixtures:tt $opts:tt $attrs:tt test $($remainder:tt)* ) => {
+        galvanic_test::__test_suite_int!(@test $tests $fixtures $opts $attrs test $($remainder)*);
+    };
+
+    // internal: async test in test_suite, its body is the first block
+    ( @async_test $tests:tt $fixtures:tt $opts:tt $attrs:tt [$($done:tt)*] { $($body:tt)* } $($remainder:tt)* ) => {
+        galvanic_test::__test_suite_int!(@test $tests $fixtures $opts $attrs $($done)* {
+            ::galvanic_test::block_on(async { $($body)* })
+        } $($remainder)*);
+    };
+    ( @async_test $tests:tt $fixtures:tt $opts:tt $attrs:tt [$($done:tt)*] $token:tt $($remainder:tt)* ) => {
+        galvanic_test::__test_suite_int!(@async_test $tests $fixtures $opts $attrs [$($done)* $token] $($remainder)*);
+    };
+
+    ( @test [$($tests:tt)*] $fixtures:tt [] [$($attrs:tt)*] test $name:ident ( $($fixture:ident $(($($expr:expr),*))*),* )
+            $(-> $ret_ty:ty)? $body:block
+            $($remainder:tt)*
+    ) => {
+        test!( @attrs [let _suite_test = __GALVANIC_TEST_SUITE.enter(&[$(_galvanic__stringify!($fixture)),*]);] [] []
+               $($attrs)* $name | $($fixture $(($($expr),*))* ),* | $(-> $ret_ty)? $body);
+        galvanic_test::__test_suite_int!(@int [$($tests)* (&[$(_galvanic__stringify!($fixture)),*], 1),] $fixtures $($remainder)*);
+    };
+    // a test function is generated for each statically known parameterisation
+    ( @test [$($tests:tt)*] [$($fixtures:tt)*] [per_case] [$($attrs:tt)*]
+            test $name:ident ( $($fixture:ident $(($($expr:expr),*))*),* )
+            $(-> $ret_ty:ty)? $body:block
             $($remainder:tt)*
     ) => {
-        test!( $(#[$attr])* $name | $($fixture $(($($expr),*))* ),* | $body);
-        galvanic_test::__test_suite_int!(@int $($remainder)*);
+        ::galvanic_test::__per_case_tests! {
+            fixtures [$($fixtures)*]
+            prelude [let _suite_test = __GALVANIC_TEST_SUITE.enter(&[$(_galvanic__stringify!($fixture)),*]);]
+            test [$($attrs)*] $name ( $($fixture $(($($expr),*))*),* ) [$(-> $ret_ty)?] $body
+        }
+        galvanic_test::__test_suite_int!(@int
+            [$($tests)* (&[$(_galvanic__stringify!($fixture)),*], $name::__GALVANIC_CASES),] [$($fixtures)*] $($remainder)*);
+    };
+    ( @test $tests:tt $fixtures:tt $opts:tt $attrs:tt test $name:ident ( $($uses:tt)* ) $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!([$name]
+            "a test must be declared as `test name(fixture, other_fixture(params...)) { ... }` with an optional `-> Type`");
+    };
+    ( @test $tests:tt $fixtures:tt $opts:tt $attrs:tt test $name:ident $($remainder:tt)* ) => {
+        ::galvanic_test::__dsl_error!([$name] "the test `{}` must list its fixtures in parentheses, e.g., `{}() { ... }`");
     };
 
     // internal: arbitrary item in test suite
-    ( @int $item:item
+    ( @int $tests:tt $fixtures:tt $item:item
             $($remainder:tt)*
     ) => {
         $item
-        galvanic_test::__test_suite_int!(@int $($remainder)*);
+        galvanic_test::__test_suite_int!(@int $tests $fixtures $($remainder)*);
+    };
+
+    // internal: end of test suite
+    ( @int [$($tests:tt)*] $fixtures:tt ) => {
+        #[allow(dead_code)]
+        static __GALVANIC_TEST_SUITE: ::galvanic_test::SuiteTracker =
+            ::galvanic_test::SuiteTracker::new(&[$($tests)*]);
+    };
+}
+
+/// Skips the current test case, e.g., if the environment lacks a resource required by a fixture.
+///
+/// The reason is given like the arguments of `format!` and is printed instead of failing the test case.
+/// For tests with parameterised fixtures only the current parameterisation is skipped.
+#[macro_export(local_inner_macros)]
+macro_rules! skip {
+    ( $($reason:tt)+ ) => {
+        ::galvanic_test::skip(_galvanic__format!($($reason)+))
     };
+}
 
-    // internal: empty test suite
-    ( @int ) => { };
+/// Asserts that the given value, formatted by `Display`, equals the stored snapshot of the current test case.
+///
+/// Each parameterisation of a test has its own snapshot, see `assert_snapshot`.
+#[macro_export]
+macro_rules! assert_snapshot {
+    ( $actual:expr $(,)? ) => {
+        ::galvanic_test::assert_snapshot(&$actual)
+    };
 }
 
 #[doc(hidden)]
@@ -347,3 +1290,11 @@ macro_rules! _galvanic__concat {
         concat!($($inner)*)
     };
 }
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _galvanic__stringify {
+    ($($inner:tt)*) => {
+        stringify!($($inner)*)
+    };
+}
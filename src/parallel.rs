@@ -0,0 +1,62 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Executing the parameterisations of a test in parallel.
+//!
+//! The combinations of the fixtures' cases are distributed across a pool of threads.
+//! Each thread takes the next combination which has not been executed yet until all combinations are done.
+
+use std::num::NonZeroUsize;
+use std::thread;
+
+use crate::report::TestReport;
+use crate::snapshot::inherit_snapshots;
+use crate::timeout::inherit_supervision;
+
+/// Executes `case` for each combination on a pool of `threads` threads,
+/// defaulting to the available parallelism of the machine.
+///
+/// The threads are named like the test's thread, are supervised if the test is supervised
+/// and assert the snapshots of the test.
+#[doc(hidden)]
+pub fn run_parallel<C>(threads: Option<usize>, report: &TestReport, combinations: &[Vec<usize>], case: C)
+where
+    C: Fn(&[usize]) + Sync,
+{
+    let threads = threads
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, NonZeroUsize::get))
+        .clamp(1, combinations.len().max(1));
+    let enter_supervision = inherit_supervision();
+    let enter_snapshots = inherit_snapshots();
+    let name = thread::current().name().map(String::from);
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            let mut worker = thread::Builder::new();
+            if let Some(ref name) = name {
+                worker = worker.name(name.clone());
+            }
+            worker
+                .spawn_scoped(scope, || {
+                    enter_supervision();
+                    enter_snapshots();
+                    while let Some(combination) = report.next_combination(combinations) {
+                        case(combination);
+                    }
+                })
+                .expect("Could not spawn a thread executing the parameterised test cases");
+        }
+    });
+}
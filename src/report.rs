@@ -0,0 +1,633 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Reporting the outcome of the parameterisations of a test.
+
+use std::any::Any;
+use std::env;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread::{self, ThreadId};
+use std::time::Duration;
+
+use crate::case::Expectation;
+use crate::combinations::{self, Combinator};
+use crate::shrink::Shrunk;
+use crate::skip::Skip;
+use crate::snapshot::restart_case;
+use crate::timeout::{notify_progress, TimedSection};
+
+/// The environment variable restricting the executed parameterisations to those whose description contains its value.
+const CASE_FILTER_VAR: &str = "GALVANIC_CASE";
+
+/// The error of a fallible fixture whose setup failed.
+///
+/// A fixture error is not a failure of the test itself as the test has not been executed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixtureError {
+    fixture: &'static str,
+    parameterisation: String,
+    error: String,
+}
+
+impl FixtureError {
+    /// Creates the error for a failed setup of the `fixture` with the given `parameterisation`.
+    pub fn new<P, E>(fixture: &'static str, parameterisation: &P, error: &E) -> Self
+    where
+        P: fmt::Debug + ?Sized,
+        E: fmt::Debug + ?Sized,
+    {
+        FixtureError {
+            fixture,
+            parameterisation: format!("{:?}", parameterisation),
+            error: format!("{:?}", error),
+        }
+    }
+
+    /// The name of the fixture which could not be set up.
+    pub fn fixture(&self) -> &'static str {
+        self.fixture
+    }
+
+    /// The description of the fixture's parameterisation.
+    pub fn parameterisation(&self) -> &str {
+        &self.parameterisation
+    }
+
+    /// The description of the error returned by the fixture's setup.
+    pub fn error(&self) -> &str {
+        &self.error
+    }
+}
+
+impl fmt::Display for FixtureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The fixture `{}` could not be set up with the parameterisation {}: {}",
+            self.fixture, self.parameterisation, self.error
+        )
+    }
+}
+
+impl std::error::Error for FixtureError {}
+
+/// The reason a parameterisation of a test did not pass.
+#[doc(hidden)]
+#[derive(Debug)]
+pub enum CaseError {
+    /// A fixture of the test case could not be set up.
+    Fixture(FixtureError),
+    /// The test returned an error.
+    Failed(String),
+}
+
+impl From<FixtureError> for CaseError {
+    fn from(error: FixtureError) -> Self {
+        CaseError::Fixture(error)
+    }
+}
+
+/// A parameterisation of a test which did not pass, was skipped or only passed on retry.
+struct ReportedCase {
+    described_parameters: String,
+    message: String,
+}
+
+/// The parameterisation a thread executes.
+struct RunningCase {
+    thread: ThreadId,
+    described_parameters: String,
+    /// Whether the final outcome of the parameterisation has been recorded.
+    recorded: bool,
+}
+
+/// Extracts the message of a panic's payload.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        String::from(*message)
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("<panicked with a payload which is not a string>")
+    }
+}
+
+/// Executes a test without parameterised fixtures up to `retries` more times until it passes.
+#[doc(hidden)]
+pub fn retried<T: FnMut()>(retries: u32, mut test: T) {
+    let mut attempt = 0;
+    loop {
+        restart_case();
+        match panic::catch_unwind(AssertUnwindSafe(&mut test)) {
+            Ok(()) => {
+                if attempt > 0 {
+                    println!("The test passed on retry {}.", attempt);
+                }
+                return;
+            }
+            // a skipped test is not retried
+            Err(payload) => {
+                if attempt >= retries || payload.is::<Skip>() {
+                    panic::resume_unwind(payload);
+                }
+                println!("Retrying the test after the failed attempt {}.\n", attempt + 1);
+            }
+        }
+        attempt += 1;
+    }
+}
+
+/// The outcome of executing a parameterisation compared to its expected outcome.
+enum Outcome {
+    /// The parameterisation has not been executed as it is not selected by `GALVANIC_CASE`.
+    Unselected,
+    Passed,
+    Skipped(String),
+    FixtureError(FixtureError),
+    /// The `explanation` is printed right after the failure, the `message` is listed in the summary.
+    Failed { explanation: String, message: String },
+}
+
+/// How a parameterisation has been recorded.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recorded {
+    /// The parameterisation is done, without a failure of the test.
+    Done,
+    /// The parameterisation failed and should be executed again.
+    Retry,
+    /// The parameterisation failed; the failure is identified by the index.
+    Failed(usize),
+}
+
+/// Selects the parameterisations of a test to execute and collects their outcomes.
+///
+/// The outcomes may be recorded concurrently by the threads executing the parameterisations of a `parallel` test.
+/// A parameterisation which timed out is recorded as failed by the watchdog of the test, see `supervise_cases`.
+/// The thread executing it is abandoned, its outcomes are not recorded anymore.
+#[doc(hidden)]
+pub struct TestReport {
+    next_combination: AtomicUsize,
+    running_cases: Mutex<Vec<RunningCase>>,
+    abandoned_threads: Mutex<Vec<ThreadId>>,
+    passed_cases: AtomicUsize,
+    failed_cases: Mutex<Vec<ReportedCase>>,
+    fixture_errors: Mutex<Vec<ReportedCase>>,
+    skipped_cases: Mutex<Vec<ReportedCase>>,
+    retried_cases: Mutex<Vec<ReportedCase>>,
+    seeds: Mutex<Vec<u64>>,
+    case_filter: Option<String>,
+    combinator: Combinator,
+    expected_panic: Option<String>,
+    skip_reason: Option<String>,
+    timeout: Option<Duration>,
+    retries: u32,
+}
+
+impl Default for TestReport {
+    fn default() -> Self {
+        TestReport {
+            next_combination: AtomicUsize::new(0),
+            running_cases: Mutex::new(Vec::new()),
+            abandoned_threads: Mutex::new(Vec::new()),
+            passed_cases: AtomicUsize::new(0),
+            failed_cases: Mutex::new(Vec::new()),
+            fixture_errors: Mutex::new(Vec::new()),
+            skipped_cases: Mutex::new(Vec::new()),
+            retried_cases: Mutex::new(Vec::new()),
+            seeds: Mutex::new(Vec::new()),
+            case_filter: env::var(CASE_FILTER_VAR).ok(),
+            combinator: combinations::product,
+            expected_panic: None,
+            skip_reason: None,
+            timeout: None,
+            retries: 0,
+        }
+    }
+}
+
+impl TestReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the strategy for combining the cases of the test's fixtures.
+    pub fn combine(&mut self, combinator: Combinator) {
+        self.combinator = combinator;
+    }
+
+    /// Expects every parameterisation to panic with a message containing `message`.
+    pub fn expect_panic<M: Into<String>>(&mut self, message: M) {
+        self.expected_panic = Some(message.into());
+    }
+
+    /// Fails a parameterisation of the test if it does not finish within `timeout`, including the setup and tear down of its fixtures.
+    pub fn timeout(&mut self, timeout: Duration) {
+        self.timeout = Some(timeout);
+    }
+
+    /// Enters the timed section of executing a parameterisation of the test.
+    pub fn time_case(&self, described_parameters: &str) -> TimedSection {
+        let thread = thread::current().id();
+        if let Some(case) = self.running_cases.lock().unwrap().iter_mut().find(|case| case.thread == thread) {
+            case.described_parameters = String::from(described_parameters);
+        }
+        TimedSection::enter(self.timeout, || {
+            if described_parameters.is_empty() {
+                String::from("the test")
+            } else {
+                format!("the test case with the parameterisation {}", described_parameters)
+            }
+        })
+    }
+
+    /// Executes a failing parameterisation of the test up to `retries` more times until it passes.
+    pub fn retry(&mut self, retries: u32) {
+        self.retries = retries;
+    }
+
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// Skips all parameterisations of the test for the given `reason`.
+    pub fn skip(&mut self, reason: String) {
+        self.skip_reason = Some(reason);
+    }
+
+    pub fn is_skipped(&self) -> bool {
+        self.skip_reason.is_some()
+    }
+
+    /// Whether all parameterisations of the test have been executed, i.e. none has been skipped or filtered out.
+    pub fn is_complete(&self) -> bool {
+        self.case_filter.is_none() && !self.is_skipped() && self.skipped_cases.lock().unwrap().is_empty()
+    }
+
+    /// The combinations of the fixtures' cases to execute, given the number of cases of each fixture.
+    ///
+    /// # Panics
+    /// If the combination strategy of the test returns a combination which does not select a case of each fixture.
+    pub fn combinations(&self, sizes: &[usize]) -> Vec<Vec<usize>> {
+        if self.is_skipped() {
+            return Vec::new();
+        }
+        let combinations = (self.combinator)(sizes);
+        for combination in &combinations {
+            let is_valid = combination.len() == sizes.len()
+                && combination.iter().zip(sizes).all(|(&index, &size)| index < size);
+            if !is_valid {
+                panic!(
+                    "The combination strategy returned the invalid combination {:?} for fixtures with {:?} cases.",
+                    combination, sizes
+                );
+            }
+        }
+        combinations
+    }
+
+    /// Takes the next of the `combinations` which has not been executed yet by any thread.
+    ///
+    /// The combinations are taken in order, also across the repeated executions of a test
+    /// which continue with the remaining combinations after a timeout.
+    /// There is no next combination for a thread whose parameterisation timed out.
+    pub fn next_combination<'c>(&self, combinations: &'c [Vec<usize>]) -> Option<&'c Vec<usize>> {
+        let thread = thread::current().id();
+        if self.is_abandoned(thread) {
+            return None;
+        }
+        let mut running_cases = self.running_cases.lock().unwrap();
+        let combination = combinations.get(self.next_combination.fetch_add(1, Ordering::SeqCst))?;
+        running_cases.retain(|case| case.thread != thread);
+        running_cases.push(RunningCase {
+            thread,
+            described_parameters: String::new(),
+            recorded: false,
+        });
+        Some(combination)
+    }
+
+    /// Whether the outcomes of all parameterisations taken by `next_combination` have been recorded.
+    pub fn is_done(&self) -> bool {
+        self.running_cases.lock().unwrap().iter().all(|case| case.recorded)
+    }
+
+    fn is_abandoned(&self, thread: ThreadId) -> bool {
+        self.abandoned_threads.lock().unwrap().contains(&thread)
+    }
+
+    /// Records the parameterisation executed by the `thread` as failed because it timed out and abandons the thread.
+    ///
+    /// Returns whether the `thread` executed a parameterisation. A timeout while shrinking a recorded failure
+    /// only stops shrinking it.
+    pub(crate) fn timed_out(&self, thread: ThreadId, message: String) -> bool {
+        let mut running_cases = self.running_cases.lock().unwrap();
+        let case = match running_cases.iter_mut().find(|case| case.thread == thread) {
+            Some(case) => case,
+            None => return false,
+        };
+        self.abandoned_threads.lock().unwrap().push(thread);
+        if !case.recorded {
+            case.recorded = true;
+            println!(
+                "The test case {} with the following parameterisation of the test case:\n    {}\n",
+                message, case.described_parameters
+            );
+            self.failed_cases.lock().unwrap().push(ReportedCase {
+                described_parameters: case.described_parameters.clone(),
+                message,
+            });
+        }
+        true
+    }
+
+    /// Remembers the seeds of generated parameters to report them if the test fails.
+    pub fn generated_with<I: IntoIterator<Item = u64>>(&self, seeds: I) {
+        let mut known_seeds = self.seeds.lock().unwrap();
+        for seed in seeds {
+            if !known_seeds.contains(&seed) {
+                known_seeds.push(seed);
+            }
+        }
+    }
+
+    /// Whether the parameterisation should be executed given the filter set by `GALVANIC_CASE`.
+    pub fn is_selected(&self, described_parameters: &str) -> bool {
+        match self.case_filter {
+            Some(ref filter) => described_parameters.contains(filter.as_str()),
+            None => true,
+        }
+    }
+
+    /// Records a passed parameterisation. Passing only on retry is reported to keep flaky tests visible.
+    fn record_pass(&self, described_parameters: &str, attempt: u32) {
+        self.passed_cases.fetch_add(1, Ordering::SeqCst);
+        if attempt > 0 {
+            println!(
+                "The test case passed on retry {} with the following parameterisation of the test case:\n    {}\n",
+                attempt, described_parameters
+            );
+            self.retried_cases.lock().unwrap().push(ReportedCase {
+                described_parameters: String::from(described_parameters),
+                message: format!("passed on retry {}", attempt),
+            });
+        }
+    }
+
+    /// Judges the result of executing a single parameterisation of the test with the given expected outcome.
+    fn judge(
+        &self,
+        result: thread::Result<Result<(), CaseError>>,
+        described_parameters: &str,
+        expectation: &Expectation,
+    ) -> Outcome {
+        // the expected panic of the test applies to all cases without an expected outcome of their own
+        let expectation = match (expectation, &self.expected_panic) {
+            (Expectation::Pass, Some(message)) => Expectation::Panic(message.clone()),
+            (expectation, _) => expectation.clone(),
+        };
+        // a case skipped by `skip!` is skipped regardless of its expected outcome
+        let (result, expectation) = match result {
+            Err(payload) => match payload.downcast::<Skip>() {
+                Ok(skip) => (Ok(Ok(())), Expectation::Skip(skip.0)),
+                Err(payload) => (Err(payload), expectation),
+            },
+            result => (result, expectation),
+        };
+        match (result, expectation) {
+            // parameterisations skipped by the filter are not executed and thus do not pass
+            (Ok(Ok(())), _) if !self.is_selected(described_parameters) => Outcome::Unselected,
+            (Ok(Ok(())), Expectation::Skip(reason)) => Outcome::Skipped(reason),
+            (Ok(Ok(())), Expectation::Pass) | (Ok(Err(CaseError::Failed(_))), Expectation::Fail) | (Err(_), Expectation::Fail) => {
+                Outcome::Passed
+            }
+            (Ok(Ok(())), Expectation::Fail) => Outcome::Failed {
+                explanation: format!(
+                    "The test case passed unexpectedly with the following parameterisation of the test case:\n    {}\n",
+                    described_parameters
+                ),
+                message: String::from("XPASS: passed although it is expected to fail"),
+            },
+            (Ok(Ok(())), Expectation::Panic(expected)) => Outcome::Failed {
+                explanation: format!(
+                    "The test case did not panic with the following parameterisation of the test case:\n    {}\n",
+                    described_parameters
+                ),
+                message: format!("did not panic, expected a panic containing {:?}", expected),
+            },
+            (Ok(Err(CaseError::Fixture(error))), _) => Outcome::FixtureError(error),
+            (Ok(Err(CaseError::Failed(error))), _) => Outcome::Failed {
+                explanation: format!(
+                    "The test case returned the error {} with the following parameterisation of the test case:\n    {}\n",
+                    error, described_parameters
+                ),
+                message: format!("returned the error {}", error),
+            },
+            (Err(payload), Expectation::Panic(expected)) => {
+                let message = panic_message(&*payload);
+                if message.contains(expected.as_str()) {
+                    return Outcome::Passed;
+                }
+                Outcome::Failed {
+                    explanation: format!(
+                        "The above panic does not contain the expected message {:?}. It occured with the following parameterisation of the test case:\n    {}\n",
+                        expected, described_parameters
+                    ),
+                    message: format!("panicked with an unexpected message, expected a panic containing {:?}: {}", expected, message),
+                }
+            }
+            (Err(payload), _) => Outcome::Failed {
+                explanation: format!(
+                    "The above error occured with the following parameterisation of the test case:\n    {}\n",
+                    described_parameters
+                ),
+                message: format!("panicked: {}", panic_message(&*payload)),
+            },
+        }
+    }
+
+    /// Records the result of executing a single parameterisation of the test with the given expected outcome.
+    ///
+    /// The `attempt` counts the previous executions of the parameterisation.
+    pub fn record(
+        &self,
+        result: thread::Result<Result<(), CaseError>>,
+        described_parameters: &str,
+        expectation: &Expectation,
+        attempt: u32,
+    ) -> Recorded {
+        let thread = thread::current().id();
+        // locked while recording so that the parameterisation is not recorded by the watchdog at the same time
+        let mut running_cases = self.running_cases.lock().unwrap();
+        // the watchdog already recorded the parameterisation as timed out
+        if self.is_abandoned(thread) {
+            return Recorded::Done;
+        }
+        let recorded = self.record_outcome(result, described_parameters, expectation, attempt);
+        if recorded != Recorded::Retry {
+            if let Some(case) = running_cases.iter_mut().find(|case| case.thread == thread) {
+                case.recorded = true;
+            }
+            drop(running_cases);
+            notify_progress();
+        }
+        recorded
+    }
+
+    /// Records the outcome of a parameterisation, see `record`.
+    fn record_outcome(
+        &self,
+        result: thread::Result<Result<(), CaseError>>,
+        described_parameters: &str,
+        expectation: &Expectation,
+        attempt: u32,
+    ) -> Recorded {
+        let reported_case = |message: String| ReportedCase {
+            described_parameters: String::from(described_parameters),
+            message,
+        };
+        match self.judge(result, described_parameters, expectation) {
+            Outcome::Unselected => Recorded::Done,
+            Outcome::Passed => {
+                self.record_pass(described_parameters, attempt);
+                Recorded::Done
+            }
+            Outcome::Skipped(reason) => {
+                println!("Skipped the following parameterisation of the test case: {}\n    {}\n", reason, described_parameters);
+                self.skipped_cases.lock().unwrap().push(reported_case(format!("skipped: {}", reason)));
+                Recorded::Done
+            }
+            Outcome::FixtureError(error) => {
+                println!("Fixture error: {}\n", error);
+                self.fixture_errors.lock().unwrap().push(reported_case(error.to_string()));
+                Recorded::Done
+            }
+            Outcome::Failed { explanation, message } => {
+                println!("{}", explanation);
+                // only failures of the test itself are retried
+                if attempt < self.retries {
+                    println!("Retrying the test case after the failed attempt {}.\n", attempt + 1);
+                    return Recorded::Retry;
+                }
+                let mut failed_cases = self.failed_cases.lock().unwrap();
+                failed_cases.push(reported_case(message));
+                Recorded::Failed(failed_cases.len() - 1)
+            }
+        }
+    }
+
+    /// The failure message if the result of executing a parameterisation is a failure of the test.
+    ///
+    /// In contrast to `record` the result is not reported.
+    pub fn failure(
+        &self,
+        result: thread::Result<Result<(), CaseError>>,
+        described_parameters: &str,
+        expectation: &Expectation,
+    ) -> Option<String> {
+        match self.judge(result, described_parameters, expectation) {
+            Outcome::Failed { message, .. } => Some(message),
+            _ => None,
+        }
+    }
+
+    /// Replaces a recorded failure by the minimal failing parameterisation found by shrinking it.
+    pub fn shrunk(&self, failure: usize, minimal: Shrunk) {
+        if self.is_abandoned(thread::current().id()) {
+            return;
+        }
+        println!(
+            "Shrunk the failing parameterisation in {} steps to the following parameterisation of the test case:\n    {}\n",
+            minimal.steps, minimal.described_parameters
+        );
+        let mut failed_cases = self.failed_cases.lock().unwrap();
+        let failed_case = &mut failed_cases[failure];
+        failed_case.message = format!(
+            "{}\nshrunk in {} steps from {}",
+            minimal.message, minimal.steps, failed_case.described_parameters
+        );
+        failed_case.described_parameters = minimal.described_parameters;
+    }
+
+    /// Summarises the outcome of all executed parameterisations, listing those which did not pass.
+    fn summary(&self) -> String {
+        let failed_cases = self.failed_cases.lock().unwrap();
+        let fixture_errors = self.fixture_errors.lock().unwrap();
+        let skipped_cases = self.skipped_cases.lock().unwrap();
+        let retried_cases = self.retried_cases.lock().unwrap();
+        let passed = self.passed_cases.load(Ordering::SeqCst);
+        let mut summary = format!(
+            "{} of {} parameterised test cases passed",
+            passed,
+            passed + failed_cases.len() + fixture_errors.len() + skipped_cases.len()
+        );
+        if !retried_cases.is_empty() {
+            summary.push_str(&format!(" ({} on retry)", retried_cases.len()));
+        }
+        summary.push_str(&format!(", {} failed", failed_cases.len()));
+        if !fixture_errors.is_empty() {
+            summary.push_str(&format!(", {} could not be set up", fixture_errors.len()));
+        }
+        if !skipped_cases.is_empty() {
+            summary.push_str(&format!(", {} skipped", skipped_cases.len()));
+        }
+        summary.push(':');
+        let reported_cases = failed_cases.iter().chain(fixture_errors.iter()).chain(skipped_cases.iter());
+        for reported_case in reported_cases.chain(retried_cases.iter()) {
+            summary.push_str(&format!(
+                "\n  - {}\n    {}",
+                reported_case.described_parameters,
+                reported_case.message.replace('\n', "\n    ")
+            ));
+        }
+        summary
+    }
+
+    /// Fails the test if any of its parameterisations failed.
+    ///
+    /// The panic message summarises the outcome of all parameterisations
+    /// so that the failure is understandable without the test's captured output.
+    pub fn finish(&self) {
+        if let Some(ref reason) = self.skip_reason {
+            println!("The test was skipped: {}", reason);
+            return;
+        }
+        let failed = self.failed_cases.lock().unwrap().len();
+        let fixture_errors = self.fixture_errors.lock().unwrap().len();
+        let headline = match (failed, fixture_errors) {
+            (0, 0) => {
+                let retried = self.retried_cases.lock().unwrap().len();
+                if retried > 0 {
+                    println!("{}", self.summary());
+                }
+                return;
+            }
+            (_, 0) => "Some parameterised test cases failed",
+            (0, _) => "Some fixtures of the test could not be set up",
+            (_, _) => "Some parameterised test cases failed and some fixtures could not be set up",
+        };
+        let mut replay = String::new();
+        for seed in self.seeds.lock().unwrap().iter() {
+            replay.push_str(&format!(
+                "\n\nSome parameters were generated with the seed {0}. Set GALVANIC_SEED={0} to replay the test.",
+                seed
+            ));
+        }
+        panic!("{}\n\n{}{}", headline, self.summary(), replay);
+    }
+}
@@ -0,0 +1,84 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The runtime executing async tests and async fixture setups and tear downs.
+//!
+//! The runtime is selected with the crate's features:
+//! * `tokio` runs futures on a single-threaded tokio runtime,
+//! * `async-std` runs futures with `async_std::task::block_on`,
+//! * otherwise a minimal built-in executor is used which does not provide any I/O or timers.
+//!
+//! If both features are enabled tokio is used.
+
+use std::future::Future;
+
+/// Runs the `future` to completion on the current thread.
+///
+/// # Panics
+/// If the future panics or if it is called from within another call to `block_on`.
+#[cfg(feature = "tokio")]
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    thread_local! {
+        // values set up by async fixtures may be bound to the runtime so it is shared by all fixtures and tests of a thread
+        static RUNTIME: tokio::runtime::Runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Could not create the tokio runtime for async tests");
+    }
+    RUNTIME.with(|runtime| runtime.block_on(future))
+}
+
+/// Runs the `future` to completion on the current thread.
+///
+/// # Panics
+/// If the future panics.
+#[cfg(all(feature = "async-std", not(feature = "tokio")))]
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    async_std::task::block_on(future)
+}
+
+/// Runs the `future` to completion on the current thread.
+///
+/// # Panics
+/// If the future panics.
+#[cfg(not(any(feature = "tokio", feature = "async-std")))]
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread::{self, Thread};
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+
+        fn wake_by_ref(self: &Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            // spurious wake ups only cause an additional poll
+            Poll::Pending => thread::park(),
+        }
+    }
+}
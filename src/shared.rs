@@ -0,0 +1,239 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Support for fixtures which are shared between several tests.
+//!
+//! The items of this module are used by the code generated for `scope`d fixtures
+//! and are not meant to be used directly.
+
+use std::os::raw::c_int;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, Once, PoisonError};
+
+/// A shared fixture which can be torn down once it is no longer needed.
+pub trait SharedTearDown: Sync {
+    /// The name of the fixture used in diagnostics.
+    fn name(&self) -> &'static str;
+
+    /// Tears down the fixture if it is currently set up.
+    fn tear_down(&self);
+}
+
+/// The shared value together with the fixture object which tears it down.
+type SharedState<R> = Option<(Arc<R>, Box<dyn Send>)>;
+
+/// The lazily set up state of a fixture shared between several tests.
+///
+/// The fixture is set up by the first test requesting it.
+/// All other tests receive the same value, even if they are executed in parallel.
+/// The value is torn down by dropping the fixture object which set it up.
+pub struct SharedFixture<R> {
+    name: &'static str,
+    state: Mutex<SharedState<R>>,
+}
+
+impl<R> SharedFixture<R>
+where
+    R: Send + Sync + 'static,
+{
+    pub const fn new(name: &'static str) -> Self {
+        SharedFixture {
+            name,
+            state: Mutex::new(None),
+        }
+    }
+
+    /// Returns the shared value, calling `setup` if the fixture is not set up yet.
+    ///
+    /// The `setup` closure returns the value and the fixture object responsible for tearing it down.
+    /// If a `suite` is given the fixture is torn down after the last test of the suite injecting it finished.
+    /// In any case it is torn down at the exit of the process if it is still alive.
+    pub fn get_or_setup<S>(&'static self, suite: Option<&'static SuiteTracker>, setup: S) -> Arc<R>
+    where
+        S: FnOnce() -> (R, Box<dyn Send>),
+    {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some((ref value, _)) = *state {
+            return value.clone();
+        }
+
+        let (value, fixture) = setup();
+        let value = Arc::new(value);
+        *state = Some((value.clone(), fixture));
+
+        if let Some(suite) = suite {
+            suite.register(self);
+        }
+        tear_down_at_exit(self);
+        value
+    }
+}
+
+impl<R> SharedTearDown for SharedFixture<R>
+where
+    R: Send + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn tear_down(&self) {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner).take();
+        if let Some((value, fixture)) = state {
+            drop(value);
+            drop(fixture);
+        }
+    }
+}
+
+/// The names of the fixtures injected into a test function of a suite and the number of such test functions.
+///
+/// A test with `#[per_case]` is expanded into a test function for each case, all injecting the same fixtures.
+pub type SuiteTest = (&'static [&'static str], usize);
+
+/// Keeps track of the tests of a test suite which have finished.
+///
+/// A fixture registered with the suite is torn down once the last test injecting it is done.
+/// Fixtures which are only set up as dependencies of other fixtures are not counted by the tests
+/// and are torn down after the last test of the suite instead.
+/// If not all tests are executed, e.g., because they are filtered or ignored,
+/// the fixtures are torn down at the exit of the process instead.
+pub struct SuiteTracker {
+    tests: &'static [SuiteTest],
+    state: Mutex<SuiteState>,
+}
+
+#[derive(Default)]
+struct SuiteState {
+    finished_tests: usize,
+    // the number of finished tests injecting the fixture of the given name
+    finished_uses: Vec<(&'static str, usize)>,
+    fixtures: Vec<&'static dyn SharedTearDown>,
+}
+
+impl SuiteTracker {
+    pub const fn new(tests: &'static [SuiteTest]) -> Self {
+        SuiteTracker {
+            tests,
+            state: Mutex::new(SuiteState {
+                finished_tests: 0,
+                finished_uses: Vec::new(),
+                fixtures: Vec::new(),
+            }),
+        }
+    }
+
+    /// Marks the start of a test in the suite which injects the named fixtures.
+    /// The test is finished once the returned guard is dropped.
+    pub fn enter(&'static self, fixtures: &'static [&'static str]) -> SuiteTestGuard {
+        SuiteTestGuard { suite: self, fixtures }
+    }
+
+    fn register(&self, fixture: &'static dyn SharedTearDown) {
+        self.lock().fixtures.push(fixture);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SuiteState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The number of test functions of the suite injecting the fixture named `fixture`.
+    fn uses(&self, fixture: &str) -> usize {
+        self.tests
+            .iter()
+            .filter(|(fixtures, _)| fixtures.contains(&fixture))
+            .map(|&(_, count)| count)
+            .sum()
+    }
+
+    fn finish_test(&self, fixtures: &[&'static str]) {
+        let mut state = self.lock();
+        state.finished_tests += 1;
+        for &fixture in fixtures {
+            match state.finished_uses.iter_mut().find(|(name, _)| *name == fixture) {
+                Some((_, finished)) => *finished += 1,
+                None => state.finished_uses.push((fixture, 1)),
+            }
+        }
+
+        let done = if state.finished_tests == self.tests.iter().map(|&(_, count)| count).sum::<usize>() {
+            std::mem::take(&mut state.fixtures)
+        } else {
+            let SuiteState {
+                ref finished_uses,
+                ref mut fixtures,
+                ..
+            } = *state;
+            let (done, used): (Vec<_>, Vec<_>) = fixtures.drain(..).partition(|fixture| {
+                finished_uses
+                    .iter()
+                    .any(|&(name, finished)| name == fixture.name() && finished == self.uses(name))
+            });
+            *fixtures = used;
+            done
+        };
+        drop(state);
+        tear_down_all(done);
+    }
+}
+
+/// Marks a running test of a suite. See `SuiteTracker::enter`.
+pub struct SuiteTestGuard {
+    suite: &'static SuiteTracker,
+    fixtures: &'static [&'static str],
+}
+
+impl Drop for SuiteTestGuard {
+    fn drop(&mut self) {
+        self.suite.finish_test(self.fixtures);
+    }
+}
+
+static AT_EXIT: Mutex<Vec<&'static dyn SharedTearDown>> = Mutex::new(Vec::new());
+static REGISTER_AT_EXIT: Once = Once::new();
+
+extern "C" {
+    fn atexit(callback: extern "C" fn()) -> c_int;
+}
+
+/// Registers a fixture to be torn down when the process exits.
+///
+/// `static`s are never dropped so we rely on the C runtime's `atexit` hook
+/// which is also run if the test harness terminates the process with `std::process::exit`.
+fn tear_down_at_exit(fixture: &'static dyn SharedTearDown) {
+    REGISTER_AT_EXIT.call_once(|| unsafe {
+        if atexit(tear_down_remaining) != 0 {
+            eprintln!("Could not register the tear down of shared fixtures at process exit.");
+        }
+    });
+    AT_EXIT
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(fixture);
+}
+
+extern "C" fn tear_down_remaining() {
+    let fixtures = std::mem::take(&mut *AT_EXIT.lock().unwrap_or_else(PoisonError::into_inner));
+    tear_down_all(fixtures);
+}
+
+/// Tears down the fixtures in the reverse order of their setup.
+fn tear_down_all(fixtures: Vec<&'static dyn SharedTearDown>) {
+    for fixture in fixtures.into_iter().rev() {
+        if catch_unwind(AssertUnwindSafe(|| fixture.tear_down())).is_err() {
+            eprintln!("The tear down of the shared fixture `{}` failed.", fixture.name());
+        }
+    }
+}
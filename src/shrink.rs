@@ -0,0 +1,105 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Shrinking the generated parameterisations of failing tests.
+//!
+//! The fixtures of a test have cases of different types.
+//! To search for a minimal failing parameterisation without knowing these types
+//! the cases are passed around as `AnyCase` and are recovered by their fixture's shrinker.
+
+use std::any::Any;
+
+use crate::case::Case;
+
+/// The maximal number of executions of a test while searching for a minimal failing parameterisation.
+const MAX_SHRINK_RUNS: usize = 1000;
+
+/// A case of one of the fixtures of a test whose type has been erased.
+#[doc(hidden)]
+pub type AnyCase = dyn Any;
+
+/// Proposes simpler cases for a type-erased case of a fixture.
+#[doc(hidden)]
+pub type ErasedShrinker = fn(&AnyCase) -> Vec<Box<AnyCase>>;
+
+/// Recovers a case of a fixture from its type-erased form. The `cases` of the fixture determine the type of the case.
+#[doc(hidden)]
+pub fn unerase<'c, P: 'static>(_cases: &[Case<P>], case: &'c AnyCase) -> &'c Case<P> {
+    case.downcast_ref()
+        .expect("The case does not belong to the fixture")
+}
+
+/// The shrinker for type-erased cases of the fixture with the given `cases`.
+#[doc(hidden)]
+pub fn shrinker<P: 'static>(_cases: &[Case<P>]) -> ErasedShrinker {
+    |case| {
+        let case: &Case<P> = case.downcast_ref().expect("The case does not belong to the fixture");
+        case.shrink().into_iter().map(|case| Box::new(case) as Box<AnyCase>).collect()
+    }
+}
+
+/// The minimal failing parameterisation found by shrinking a failing parameterisation.
+#[doc(hidden)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shrunk {
+    pub described_parameters: String,
+    pub message: String,
+    pub steps: usize,
+}
+
+/// Searches for simpler cases of the fixtures for which the test still fails.
+///
+/// The cases of one fixture are shrunk at a time, keeping the first simpler case for which
+/// `still_fails` returns the description of the parameterisation and the failure message.
+/// The search ends when no fixture has a simpler failing case or after `MAX_SHRINK_RUNS` executions.
+#[doc(hidden)]
+pub fn shrink<F>(fixture_cases: &[&AnyCase], shrinkers: &[ErasedShrinker], mut still_fails: F) -> Option<Shrunk>
+where
+    F: FnMut(&[&AnyCase]) -> Option<(String, String)>,
+{
+    let mut shrunk_cases: Vec<Option<Box<AnyCase>>> = fixture_cases.iter().map(|_| None).collect();
+    let mut minimal = None;
+    let mut steps = 0;
+    let mut runs = 0;
+    'shrinking: loop {
+        for (index, shrinker) in shrinkers.iter().enumerate() {
+            let candidates = shrinker(shrunk_cases[index].as_deref().unwrap_or(fixture_cases[index]));
+            for candidate in candidates {
+                if runs == MAX_SHRINK_RUNS {
+                    break 'shrinking;
+                }
+                runs += 1;
+                let mut cases: Vec<&AnyCase> = shrunk_cases
+                    .iter()
+                    .zip(fixture_cases)
+                    .map(|(shrunk_case, &fixture_case)| shrunk_case.as_deref().unwrap_or(fixture_case))
+                    .collect();
+                cases[index] = &*candidate;
+                if let Some(failure) = still_fails(&cases) {
+                    shrunk_cases[index] = Some(candidate);
+                    minimal = Some(failure);
+                    steps += 1;
+                    continue 'shrinking;
+                }
+            }
+        }
+        break;
+    }
+    minimal.map(|(described_parameters, message)| Shrunk {
+        described_parameters,
+        message,
+        steps,
+    })
+}
@@ -0,0 +1,50 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Skipping tests which cannot be executed, e.g., because the environment lacks a resource.
+//!
+//! A skipped test unwinds with a `Skip` payload which is caught by the code generated for the test.
+//! Unlike a panic it does not invoke the panic hook and is not reported as failure.
+
+use std::panic::{self, AssertUnwindSafe};
+
+/// The payload of a skipped test case.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Skip(pub String);
+
+/// Skips the current test case for the given `reason`.
+///
+/// The test case is aborted and reported as skipped instead of being executed any further.
+pub fn skip<R: Into<String>>(reason: R) -> ! {
+    panic::resume_unwind(Box::new(Skip(reason.into())))
+}
+
+/// Runs a test without parameterised fixtures, printing the reason instead of failing the test if it is skipped.
+///
+/// Returns `None` if the test has been skipped.
+#[doc(hidden)]
+pub fn skippable<R, T: FnOnce() -> R>(test: T) -> Option<R> {
+    match panic::catch_unwind(AssertUnwindSafe(test)) {
+        Ok(result) => Some(result),
+        Err(payload) => match payload.downcast::<Skip>() {
+            Ok(skip) => {
+                println!("The test was skipped: {}", skip.0);
+                None
+            }
+            Err(payload) => panic::resume_unwind(payload),
+        },
+    }
+}
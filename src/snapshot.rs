@@ -0,0 +1,324 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Snapshot assertions comparing the output of a test with a stored file.
+//!
+//! The snapshots of a test are stored in `tests/snapshots/<module path>/<test name>/` relative to the tested crate.
+//! Each parameterisation of the test has its own snapshot, named after the description of the parameterisation.
+//! Setting the environment variable `GALVANIC_UPDATE_SNAPSHOTS=1` writes the snapshots instead of comparing them
+//! and removes the snapshots which are not produced by any parameterisation of the test.
+
+use std::cell::RefCell;
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// The environment variable enabling the update of snapshots.
+const UPDATE_VAR: &str = "GALVANIC_UPDATE_SNAPSHOTS";
+
+/// The extension of snapshot files.
+const EXTENSION: &str = "snap";
+
+/// The maximal length of the file name of a snapshot, before the extension.
+const MAX_NAME_LEN: usize = 80;
+
+/// The number of unchanged lines shown around the changed lines of a snapshot.
+const DIFF_CONTEXT: usize = 3;
+
+/// The snapshots of the currently executed test.
+struct TestSnapshots {
+    manifest_dir: PathBuf,
+    directory: PathBuf,
+    produced: Mutex<Vec<PathBuf>>,
+}
+
+/// The snapshots of the currently executed parameterisation of a test.
+#[derive(Default)]
+struct CaseSnapshots {
+    name: String,
+    asserted: usize,
+}
+
+thread_local! {
+    static TEST: RefCell<Option<Arc<TestSnapshots>>> = const { RefCell::new(None) };
+    static CASE: RefCell<CaseSnapshots> = RefCell::new(CaseSnapshots::default());
+}
+
+fn is_update() -> bool {
+    matches!(env::var(UPDATE_VAR).as_deref(), Ok("1") | Ok("true"))
+}
+
+/// Turns the description of a parameterisation into the name of a snapshot file.
+fn file_name(described_parameters: &str) -> String {
+    let mut name = String::new();
+    for c in described_parameters.chars() {
+        if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+            name.push(c);
+        } else if !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    let name = name.trim_matches(|c| c == '_' || c == '.');
+    if name.is_empty() {
+        return String::from("snapshot");
+    }
+    if name.len() <= MAX_NAME_LEN {
+        return String::from(name);
+    }
+    // FNV-1a, as it is stable between runs in contrast to the hashers of the standard library
+    let hash = described_parameters
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3));
+    format!("{}_{:016x}", &name[..MAX_NAME_LEN], hash)
+}
+
+/// The snapshots of a test, which are checked for orphans once the test is finished.
+#[doc(hidden)]
+pub struct SnapshotScope {
+    snapshots: Arc<TestSnapshots>,
+}
+
+impl SnapshotScope {
+    /// Enters the test `name` defined in the module `module_path` of the crate in `manifest_dir`.
+    pub fn enter(manifest_dir: &str, module_path: &str, name: &str) -> Self {
+        let mut directory = Path::new(manifest_dir).join("tests").join("snapshots");
+        // the module of a test suite's tests is an implementation detail
+        for module in module_path.split("::").filter(|module| *module != "__galvanic_test") {
+            directory.push(module);
+        }
+        directory.push(name);
+        let snapshots = Arc::new(TestSnapshots {
+            manifest_dir: PathBuf::from(manifest_dir),
+            directory,
+            produced: Mutex::new(Vec::new()),
+        });
+        TEST.with(|test| *test.borrow_mut() = Some(snapshots.clone()));
+        CASE.with(|case| *case.borrow_mut() = CaseSnapshots::default());
+        SnapshotScope { snapshots }
+    }
+
+    /// Detects the snapshots which are not produced by the test.
+    ///
+    /// The test is `complete` if all of its parameterisations have been executed.
+    /// Otherwise no snapshot is considered to be orphaned.
+    ///
+    /// # Panics
+    /// If an orphaned snapshot is found and the snapshots should not be updated.
+    pub fn finish(self, complete: bool) {
+        if !complete {
+            return;
+        }
+        let produced = self.snapshots.produced.lock().unwrap();
+        let mut orphans: Vec<PathBuf> = match fs::read_dir(&self.snapshots.directory) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|extension| extension == EXTENSION))
+                .filter(|path| !produced.contains(path))
+                .collect(),
+            Err(_) => return,
+        };
+        if orphans.is_empty() {
+            return;
+        }
+        orphans.sort();
+        let relative = |path: &PathBuf| {
+            path.strip_prefix(&self.snapshots.manifest_dir).unwrap_or(path).display().to_string()
+        };
+        if is_update() {
+            for orphan in &orphans {
+                fs::remove_file(orphan)
+                    .unwrap_or_else(|error| panic!("Could not remove the snapshot `{}`: {}", relative(orphan), error));
+                println!("Removed the orphaned snapshot `{}`.", relative(orphan));
+            }
+            return;
+        }
+        let orphans: Vec<String> = orphans.iter().map(relative).collect();
+        panic!(
+            "The following snapshots are not produced by any parameterisation of the test:\n  - {}\n\nSet {}=1 to remove them.",
+            orphans.join("\n  - "),
+            UPDATE_VAR
+        );
+    }
+}
+
+impl Drop for SnapshotScope {
+    fn drop(&mut self) {
+        TEST.with(|test| *test.borrow_mut() = None);
+        CASE.with(|case| *case.borrow_mut() = CaseSnapshots::default());
+    }
+}
+
+/// The snapshots of a parameterisation of a test. The parameterisation ends once it is dropped.
+#[doc(hidden)]
+pub struct SnapshotCase(());
+
+impl SnapshotCase {
+    pub fn enter(described_parameters: &str) -> Self {
+        CASE.with(|case| {
+            *case.borrow_mut() = CaseSnapshots {
+                name: file_name(described_parameters),
+                asserted: 0,
+            }
+        });
+        SnapshotCase(())
+    }
+}
+
+impl Drop for SnapshotCase {
+    fn drop(&mut self) {
+        CASE.with(|case| *case.borrow_mut() = CaseSnapshots::default());
+    }
+}
+
+/// Restarts the current parameterisation of the test so that a retried attempt asserts the same snapshots.
+pub(crate) fn restart_case() {
+    CASE.with(|case| case.borrow_mut().asserted = 0);
+}
+
+/// Captures the snapshots of the test executed by the current thread so that the threads spawned by the test can assert snapshots.
+///
+/// The returned function enters the captured test in the thread calling it.
+pub(crate) fn inherit_snapshots() -> impl Fn() + Sync {
+    let snapshots = TEST.with(|test| test.borrow().clone());
+    move || TEST.with(|test| *test.borrow_mut() = snapshots.clone())
+}
+
+/// Asserts that `actual` equals the stored snapshot of the current parameterisation of the test.
+///
+/// The n-th snapshot asserted by a parameterisation is stored in the file `<parameterisation>.<n>.snap`,
+/// the first one in `<parameterisation>.snap`.
+/// If the environment variable `GALVANIC_UPDATE_SNAPSHOTS=1` is set, the snapshot is written instead.
+///
+/// # Panics
+/// If the snapshot does not exist or does not match `actual`, showing the differing lines,
+/// or if the assertion is not executed by a test defined with `test!` or `test_suite!`.
+#[track_caller]
+pub fn assert_snapshot<T: Display + ?Sized>(actual: &T) {
+    let actual = actual.to_string();
+    let snapshots = match TEST.with(|test| test.borrow().clone()) {
+        Some(snapshots) => snapshots,
+        None => panic!("Snapshots can only be asserted in tests defined by `test!` or `test_suite!`."),
+    };
+    let name = CASE.with(|case| {
+        let mut case = case.borrow_mut();
+        case.asserted += 1;
+        let name = if case.name.is_empty() { "snapshot" } else { case.name.as_str() };
+        match case.asserted {
+            1 => format!("{}.{}", name, EXTENSION),
+            n => format!("{}.{}.{}", name, n, EXTENSION),
+        }
+    });
+    let path = snapshots.directory.join(name);
+    snapshots.produced.lock().unwrap().push(path.clone());
+    let relative = path.strip_prefix(&snapshots.manifest_dir).unwrap_or(&path).display().to_string();
+
+    let expected = fs::read_to_string(&path).ok().map(|expected| expected.replace("\r\n", "\n"));
+    match expected {
+        Some(ref expected) if *expected == actual => {}
+        _ if is_update() => {
+            fs::create_dir_all(&snapshots.directory)
+                .and_then(|_| fs::write(&path, &actual))
+                .unwrap_or_else(|error| panic!("Could not write the snapshot `{}`: {}", relative, error));
+            println!("Updated the snapshot `{}`.", relative);
+        }
+        None => panic!(
+            "The snapshot `{}` does not exist. The test produced:\n{}\n\nSet {}=1 to create it.",
+            relative, actual, UPDATE_VAR
+        ),
+        Some(expected) => panic!(
+            "The snapshot `{}` does not match the output of the test (- snapshot, + output):\n{}\nSet {}=1 to update it.",
+            relative,
+            diff(&expected, &actual),
+            UPDATE_VAR
+        ),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Line<'a> {
+    Unchanged(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// The lines of `expected` and `actual` based on their longest common subsequence.
+fn diff_lines<'a>(expected: &'a str, actual: &'a str) -> Vec<Line<'a>> {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    // common[i][j] is the length of the longest common subsequence of expected[i..] and actual[j..]
+    let mut common = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            lines.push(Line::Unchanged(expected[i]));
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(Line::Removed(expected[i]));
+            i += 1;
+        } else {
+            lines.push(Line::Added(actual[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+/// Shows the changed lines between `expected` and `actual` together with some unchanged lines around them.
+fn diff(expected: &str, actual: &str) -> String {
+    let lines = diff_lines(expected, actual);
+    let changed: Vec<usize> = (0..lines.len())
+        .filter(|index| !matches!(lines[*index], Line::Unchanged(_)))
+        .collect();
+    if changed.is_empty() {
+        return String::from("  (the snapshot differs in its line endings or in a trailing newline)\n");
+    }
+    let is_shown = |index: usize| {
+        changed
+            .iter()
+            .any(|changed| index + DIFF_CONTEXT >= *changed && index <= changed + DIFF_CONTEXT)
+    };
+    let mut diff = String::new();
+    let mut skipped = false;
+    for (index, line) in lines.iter().enumerate() {
+        if !is_shown(index) {
+            if !skipped {
+                diff.push_str("  ...\n");
+                skipped = true;
+            }
+            continue;
+        }
+        skipped = false;
+        let (marker, line) = match *line {
+            Line::Unchanged(line) => (' ', line),
+            Line::Removed(line) => ('-', line),
+            Line::Added(line) => ('+', line),
+        };
+        diff.push_str(&format!("{} {}\n", marker, line));
+    }
+    diff
+}
@@ -0,0 +1,268 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Timeouts for tests and fixtures.
+//!
+//! A supervised test runs on a worker thread while the test's own thread acts as its watchdog.
+//! Code running on the worker thread enters timed sections, e.g., the setup of a fixture.
+//! If a section does not finish within its timeout the watchdog fails the test
+//! with a description of the section instead of waiting forever.
+//! The worker thread cannot be stopped and keeps running in the background.
+//!
+//! A timeout of a parameterised test only fails the parameterisation it occurs in.
+//! The watchdog records the failure in the test's report and continues the test on a new worker thread.
+
+use std::cell::RefCell;
+use std::panic;
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::thread::{self, JoinHandle, ThreadId};
+use std::time::{Duration, Instant};
+
+use crate::report::TestReport;
+use crate::snapshot::inherit_snapshots;
+
+/// A section of a supervised test which has to finish before its deadline.
+struct Section {
+    id: usize,
+    thread: ThreadId,
+    deadline: Instant,
+    timeout: Duration,
+    activity: String,
+}
+
+/// A section which did not finish before its deadline.
+struct TimedOut {
+    thread: ThreadId,
+    timeout: Duration,
+    activity: String,
+}
+
+#[derive(Default)]
+struct State {
+    sections: Vec<Section>,
+    next_id: usize,
+    /// The worker threads which finished, identified by the order they were started in.
+    finished: Vec<usize>,
+}
+
+/// The state shared by a supervised test and its watchdog.
+#[derive(Default)]
+struct Supervision {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+impl Supervision {
+    fn update<T, F: FnOnce(&mut State) -> T>(&self, update: F) -> T {
+        let result = update(&mut self.state.lock().unwrap_or_else(PoisonError::into_inner));
+        self.changed.notify_all();
+        result
+    }
+
+    /// Waits until the test is `done` or one of its sections timed out, returning the timed out section.
+    ///
+    /// The other sections of the thread which timed out are not watched anymore.
+    fn watch<D: Fn(&State) -> bool>(&self, done: D) -> Option<TimedOut> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        loop {
+            if done(&state) {
+                return None;
+            }
+            let next_deadline = state.sections.iter().min_by_key(|section| section.deadline);
+            state = match next_deadline {
+                None => self.changed.wait(state).unwrap_or_else(PoisonError::into_inner),
+                Some(section) => {
+                    let now = Instant::now();
+                    if section.deadline <= now {
+                        let timed_out = TimedOut {
+                            thread: section.thread,
+                            timeout: section.timeout,
+                            activity: section.activity.clone(),
+                        };
+                        state.sections.retain(|section| section.thread != timed_out.thread);
+                        return Some(timed_out);
+                    }
+                    let remaining = section.deadline - now;
+                    self.changed
+                        .wait_timeout(state, remaining)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+            };
+        }
+    }
+}
+
+thread_local! {
+    static SUPERVISION: RefCell<Option<Arc<Supervision>>> = const { RefCell::new(None) };
+}
+
+/// Marks a worker thread of the supervised test as finished, even if it panics.
+struct FinishGuard(Arc<Supervision>, usize);
+
+impl Drop for FinishGuard {
+    fn drop(&mut self) {
+        let worker = self.1;
+        self.0.update(|state| state.finished.push(worker));
+    }
+}
+
+/// Spawns the `worker`-th thread executing the supervised `test`, named like the test's thread.
+fn spawn_worker<T>(supervision: &Arc<Supervision>, worker: usize, test: T) -> JoinHandle<()>
+where
+    T: FnOnce() + Send + 'static,
+{
+    let worker_supervision = supervision.clone();
+    let enter_snapshots = inherit_snapshots();
+    let mut builder = thread::Builder::new();
+    if let Some(name) = thread::current().name() {
+        builder = builder.name(String::from(name));
+    }
+    builder
+        .spawn(move || {
+            let _finished = FinishGuard(worker_supervision.clone(), worker);
+            SUPERVISION.with(|supervision| *supervision.borrow_mut() = Some(worker_supervision));
+            enter_snapshots();
+            test();
+        })
+        .expect("Could not spawn the thread executing the test")
+}
+
+/// Runs a test on a worker thread, failing the test if a timed section of the test does not finish in time.
+///
+/// Panics of the test are propagated to the calling thread.
+#[doc(hidden)]
+pub fn supervise<T>(test: T)
+where
+    T: FnOnce() + Send + 'static,
+{
+    let supervision = Arc::new(Supervision::default());
+    let worker = spawn_worker(&supervision, 0, test);
+
+    match supervision.watch(|state| state.finished.contains(&0)) {
+        None => {
+            if let Err(payload) = worker.join() {
+                panic::resume_unwind(payload);
+            }
+        }
+        Some(timed_out) => panic!("The test timed out after {:?} in {}.", timed_out.timeout, timed_out.activity),
+    }
+}
+
+/// Runs the parameterisations of a test on a worker thread, failing a parameterisation if one of its timed sections
+/// does not finish in time.
+///
+/// The `test` executes the parameterisations which have not been started yet, see `TestReport::next_combination`.
+/// After a timeout it is executed again on a new worker thread to continue with the remaining parameterisations.
+/// The thread of the timed out parameterisation keeps running in the background, the parameterisation's fixtures are
+/// torn down once the timed out section finishes, provided that the test binary is still running by then.
+/// Panics of the test outside of its parameterisations are propagated to the calling thread.
+#[doc(hidden)]
+pub fn supervise_cases<T>(report: &TestReport, test: T)
+where
+    T: Fn() + Send + Sync + 'static,
+{
+    let supervision = Arc::new(Supervision::default());
+    let test = Arc::new(test);
+    let mut worker = 0;
+    let mut handle = {
+        let test = test.clone();
+        spawn_worker(&supervision, worker, move || test())
+    };
+
+    loop {
+        let current = worker;
+        // the threads of timed out parameterisations may still execute other parameterisations of a `parallel` test
+        match supervision.watch(|state| state.finished.contains(&current) && report.is_done()) {
+            None => {
+                if let Err(payload) = handle.join() {
+                    panic::resume_unwind(payload);
+                }
+                return;
+            }
+            Some(timed_out) => {
+                let message = format!("timed out after {:?} in {}", timed_out.timeout, timed_out.activity);
+                // a timeout outside of the parameterisations fails the whole test
+                if !report.timed_out(timed_out.thread, message) {
+                    panic!("The test timed out after {:?} in {}.", timed_out.timeout, timed_out.activity);
+                }
+                worker += 1;
+                let test = test.clone();
+                handle = spawn_worker(&supervision, worker, move || test());
+            }
+        }
+    }
+}
+
+/// Notifies the watchdog of the current thread's test that the test made progress.
+pub(crate) fn notify_progress() {
+    if let Some(supervision) = SUPERVISION.with(|supervision| supervision.borrow().clone()) {
+        supervision.update(|_| ());
+    }
+}
+
+/// Captures the supervision of the current thread so that the threads spawned by a test can be supervised as well.
+///
+/// The returned function enters the captured supervision in the thread calling it.
+pub(crate) fn inherit_supervision() -> impl Fn() + Sync {
+    let supervision = SUPERVISION.with(|supervision| supervision.borrow().clone());
+    move || SUPERVISION.with(|inherited| *inherited.borrow_mut() = supervision.clone())
+}
+
+/// A section of a test which has to finish within a timeout. The section ends once it is dropped.
+///
+/// The timeout is only enforced if the test is supervised, see `supervise`.
+#[doc(hidden)]
+pub struct TimedSection {
+    section: Option<(Arc<Supervision>, usize)>,
+}
+
+impl TimedSection {
+    /// Enters a section which has to finish within `timeout`. The `activity` describes the section if it times out.
+    pub fn enter<D: FnOnce() -> String>(timeout: Option<Duration>, activity: D) -> Self {
+        let supervision = match timeout {
+            Some(_) => SUPERVISION.with(|supervision| supervision.borrow().clone()),
+            None => None,
+        };
+        let section = match (timeout, supervision) {
+            (Some(timeout), Some(supervision)) => {
+                let activity = activity();
+                let id = supervision.update(|state| {
+                    let id = state.next_id;
+                    state.next_id += 1;
+                    state.sections.push(Section {
+                        id,
+                        thread: thread::current().id(),
+                        deadline: Instant::now() + timeout,
+                        timeout,
+                        activity,
+                    });
+                    id
+                });
+                Some((supervision, id))
+            }
+            _ => None,
+        };
+        TimedSection { section }
+    }
+}
+
+impl Drop for TimedSection {
+    fn drop(&mut self) {
+        if let Some((ref supervision, id)) = self.section {
+            supervision.update(|state| state.sections.retain(|section| section.id != id));
+        }
+    }
+}
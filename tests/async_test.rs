@@ -0,0 +1,170 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Returns `Pending` once before completing to force the runtime to wake up the task.
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+async fn answer() -> i32 {
+    YieldOnce(false).await;
+    42
+}
+
+mod basic {
+    use super::*;
+    use galvanic_test::{fixture, test, TestFixture};
+    use std::cell::RefCell;
+
+    thread_local! {
+        static EVENTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn record(event: String) {
+        EVENTS.with(|events| events.borrow_mut().push(event));
+    }
+
+    fixture!( async_number(offset: i32) -> i32 {
+        params {
+            vec![0, 1].into_iter()
+        }
+        async setup(&mut self) {
+            record(format!("setup {}", self.offset));
+            answer().await + self.offset
+        }
+        async tear_down(&self) {
+            YieldOnce(false).await;
+            record(format!("tear down {}", self.offset));
+        }
+    });
+
+    fixture!( async_parsed() -> Result<i32, std::num::ParseIntError> {
+        async setup(&mut self) {
+            YieldOnce(false).await;
+            let number = "21".parse::<i32>()?;
+            Ok(number * 2)
+        }
+    });
+
+    test!(
+        async run_async_test_without_fixtures {
+            assert_eq!(answer().await, 42);
+        }
+    );
+
+    test!(
+        async inject_async_fixture | async_number | {
+            assert_eq!(async_number.val, answer().await + async_number.params.offset);
+        }
+    );
+
+    test!(
+        async inject_fallible_async_fixture | async_parsed | -> Result<(), String> {
+            YieldOnce(false).await;
+            if async_parsed.val == 42 { Ok(()) } else { Err(String::from("unexpected value")) }
+        }
+    );
+
+    test!(
+        #[should_panic(expected = "Some parameterised test cases failed")]
+        async fail_async_test | async_number(7) | {
+            answer().await;
+            panic!("failed async test");
+        }
+    );
+
+    #[test]
+    fn should_tear_down_async_fixture_on_failure() {
+        assert!(std::panic::catch_unwind(fail_async_test).is_err());
+        EVENTS.with(|events| assert_eq!(*events.borrow(), vec!["setup 7", "tear down 7"]));
+    }
+
+    #[test]
+    fn should_block_on_future() {
+        assert_eq!(galvanic_test::block_on(answer()), 42);
+    }
+}
+
+mod async_test_suite {
+    use galvanic_test::test_suite;
+
+    test_suite! {
+        use super::super::answer;
+
+        fixture async_greeting(name: &'static str) -> String {
+            params {
+                vec!["Alice", "Bob"].into_iter()
+            }
+            async setup(&mut self) {
+                format!("Hello {} {}", self.name, answer().await)
+            }
+        }
+
+        async test run_async_test_in_suite() {
+            assert_eq!(answer().await, 42);
+        }
+
+        async test inject_async_fixture_in_suite(async_greeting) {
+            let expected = format!("Hello {} {}", async_greeting.params.name, answer().await);
+            assert_eq!(async_greeting.val, expected);
+        }
+
+        #[should_panic(expected = "The test returned an error")]
+        async test return_error_from_async_test() -> Result<(), String> {
+            answer().await;
+            Err(String::from("async error"))
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_runtime {
+    use galvanic_test::test;
+
+    test!(
+        async run_on_tokio_runtime {
+            assert!(tokio::runtime::Handle::try_current().is_ok());
+            tokio::task::spawn(async { super::answer().await }).await.unwrap();
+        }
+    );
+}
+
+#[cfg(all(feature = "async-std", not(feature = "tokio")))]
+mod async_std_runtime {
+    use galvanic_test::test;
+
+    test!(
+        async run_on_async_std_runtime {
+            async_std::task::yield_now().await;
+            assert_eq!(async_std::task::spawn(super::answer()).await, 42);
+        }
+    );
+}
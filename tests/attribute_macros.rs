@@ -0,0 +1,161 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use galvanic_test::{fixture, galvanic, test, FixtureBinding, TestFixture};
+use std::process::Output;
+
+/// Runs a single ignored test of this test binary, showing its output.
+fn run_ignored_test(name: &str) -> Output {
+    common::run_ignored_test_with(name, |command| {
+        command.args(["--nocapture", "--test-threads=1"]);
+    })
+}
+
+async fn answer() -> u32 {
+    42
+}
+
+/// The answer to everything.
+#[galvanic::fixture]
+fn answer_fixture() -> u32 {
+    if true {
+        return 42;
+    }
+    0
+}
+
+#[galvanic::fixture(params { vec![1, 2, 3].into_iter() })]
+fn doubled(mut number: u32) -> u32 {
+    number *= 2;
+    number
+}
+
+#[galvanic::fixture]
+fn greeting(name: String, punctuation: char) -> String {
+    format!("Hello {}{}", name, punctuation)
+}
+
+#[galvanic::teardown(greeting)]
+fn say_goodbye(name: &String) {
+    println!("Goodbye {}", name);
+}
+
+#[galvanic::fixture(params { vec![-1, 1].into_iter() })]
+fn positive(number: i32) -> Result<u32, String> {
+    if number < 0 {
+        return Err(format!("{} is negative", number));
+    }
+    Ok(number as u32)
+}
+
+#[galvanic::fixture]
+async fn async_answer() -> u32 {
+    answer().await
+}
+
+fixture!( macro_fixture(value: u32) -> u32 {
+    params { vec![10].into_iter() }
+    setup(&mut self) {
+        *self.value + 1
+    }
+});
+
+#[galvanic::test]
+fn inject_fixture_without_parameters(answer_fixture: FixtureBinding<answer_fixture, u32>) {
+    assert_eq!(answer_fixture.val, 42);
+}
+
+#[galvanic::test]
+fn test_without_fixtures() {
+    assert_eq!(1 + 1, 2);
+}
+
+#[galvanic::test]
+#[parallel]
+fn inject_parameterised_fixture(doubled: FixtureBinding<_, _>) {
+    assert_eq!(doubled.val, *doubled.params.number * 2);
+}
+
+#[galvanic::test]
+fn inject_fixture_with_arguments(#[with(String::from("World"), '!')] greeting: FixtureBinding<greeting, String>) {
+    assert_eq!(greeting.val, "Hello World!");
+}
+
+#[galvanic::test]
+fn inject_async_fixture(mut async_answer: FixtureBinding<async_answer, u32>) {
+    async_answer.val += 1;
+    assert_eq!(async_answer.val, 43);
+}
+
+#[galvanic::test]
+fn inject_fixtures_of_both_styles(doubled: FixtureBinding<doubled, u32>, macro_fixture: FixtureBinding<macro_fixture, u32>) {
+    assert!(doubled.val < macro_fixture.val);
+}
+
+test!( inject_attribute_fixture_into_macro_test | answer_fixture, doubled | {
+    assert!(doubled.val <= 6);
+    assert_eq!(answer_fixture.val, 42);
+});
+
+#[galvanic::test]
+async fn async_test(answer_fixture: FixtureBinding<answer_fixture, u32>) {
+    assert_eq!(answer().await, answer_fixture.val);
+}
+
+#[galvanic::test]
+fn test_returning_result(doubled: FixtureBinding<doubled, u32>) -> Result<(), String> {
+    if doubled.val % 2 == 1 {
+        return Err(String::from("odd"));
+    }
+    Ok(())
+}
+
+#[test]
+fn should_list_cases_of_attribute_fixtures() {
+    let cases: Vec<u32> = doubled::parameters().unwrap().collect();
+    assert_eq!(cases, vec![1, 2, 3]);
+}
+
+#[test]
+fn should_tear_down_attribute_fixtures() {
+    let output = run_ignored_test("tear_down_greeting");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Hello Galvanic?\nGoodbye Galvanic\n"), "{}", stdout);
+}
+
+#[galvanic::test]
+#[ignore]
+fn tear_down_greeting(#[with(String::from("Galvanic"), '?')] greeting: FixtureBinding<greeting, String>) {
+    println!("{}", greeting.val);
+}
+
+#[test]
+fn should_report_errors_of_fallible_attribute_fixtures() {
+    let output = run_ignored_test("inject_fallible_fixture");
+    // the summary of the failed test is printed by the panic hook as the output is not captured
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("1 of 2 parameterised test cases passed, 0 failed, 1 could not be set up:"), "{}", stderr);
+    assert!(stderr.contains("-1 is negative"), "{}", stderr);
+}
+
+#[galvanic::test]
+#[ignore]
+fn inject_fallible_fixture(positive: FixtureBinding<positive, u32>) {
+    assert!(positive.val > 0);
+}
@@ -0,0 +1,177 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use galvanic_test::combinations::{pairwise, product, zip};
+use std::collections::BTreeSet;
+
+#[test]
+fn should_combine_all_cases_by_product() {
+    assert_eq!(product(&[2, 3]), vec![vec![0, 0], vec![0, 1], vec![0, 2], vec![1, 0], vec![1, 1], vec![1, 2]]);
+    assert_eq!(product(&[2, 0]), Vec::<Vec<usize>>::new());
+    assert_eq!(product(&[]), vec![Vec::<usize>::new()]);
+}
+
+#[test]
+fn should_zip_cases_up_to_the_shortest_fixture() {
+    assert_eq!(zip(&[3, 2, 4]), vec![vec![0, 0, 0], vec![1, 1, 1]]);
+    assert_eq!(zip(&[3, 0]), Vec::<Vec<usize>>::new());
+    assert_eq!(zip(&[]), vec![Vec::<usize>::new()]);
+}
+
+#[test]
+fn should_cover_all_pairs_with_fewer_combinations() {
+    let sizes = [3, 3, 3, 3];
+    let combinations = pairwise(&sizes);
+    assert!(combinations.len() < product(&sizes).len());
+
+    let mut covered = BTreeSet::new();
+    for combination in &combinations {
+        for first in 0..sizes.len() {
+            for second in first + 1..sizes.len() {
+                covered.insert(((first, combination[first]), (second, combination[second])));
+            }
+        }
+    }
+    let all_pairs = (0..sizes.len())
+        .flat_map(|first| (first + 1..sizes.len()).map(move |second| (first, second)))
+        .map(|(first, second)| sizes[first] * sizes[second])
+        .sum::<usize>();
+    assert_eq!(covered.len(), all_pairs);
+    assert_eq!(pairwise(&sizes), combinations);
+}
+
+#[test]
+fn should_use_product_for_pairwise_combinations_of_two_fixtures() {
+    assert_eq!(pairwise(&[2, 3]), product(&[2, 3]));
+}
+
+mod strategies {
+    use galvanic_test::{fixture, test, TestFixture};
+
+    fixture!( number(x: u32) -> u32 {
+        params {
+            vec![1, 2, 3].into_iter()
+        }
+        setup(&mut self) {
+            *self.x
+        }
+    });
+
+    fixture!( tens(x: u32) -> u32 {
+        params {
+            vec![10, 20, 30, 40].into_iter()
+        }
+        setup(&mut self) {
+            *self.x
+        }
+    });
+
+    fixture!( flag(on: bool) -> bool {
+        params {
+            vec![true, false].into_iter()
+        }
+        setup(&mut self) {
+            *self.on
+        }
+    });
+
+    pub fn only_last_cases(sizes: &[usize]) -> Vec<Vec<usize>> {
+        vec![sizes.iter().map(|size| size - 1).collect()]
+    }
+
+    pub fn out_of_range(sizes: &[usize]) -> Vec<Vec<usize>> {
+        vec![sizes.to_vec()]
+    }
+
+    test!(
+        #[combine(zip)]
+        zip_cases_in_lockstep | number, tens | {
+            assert_eq!(tens.val, number.val * 10);
+        }
+    );
+
+    test!(
+        #[combine(pairwise)]
+        combine_cases_pairwise | number, tens, flag | {
+            assert!(number.val <= 3 && tens.val <= 40);
+        }
+    );
+
+    test!(
+        #[combine(product)]
+        combine_all_cases | number, tens | {
+            assert!(number.val * tens.val <= 120);
+        }
+    );
+
+    test!(
+        #[combine(self::only_last_cases)]
+        combine_with_custom_strategy | number, tens | {
+            assert_eq!(number.val, 3);
+            assert_eq!(tens.val, 40);
+        }
+    );
+
+    test!(
+        #[combine(self::out_of_range)]
+        #[should_panic(expected = "The combination strategy returned the invalid combination [3, 4]")]
+        fail_for_invalid_combinations | number, tens | {}
+    );
+}
+
+galvanic_test::test_suite! {
+    name zipped_suite;
+    use super::common::generated_tests;
+
+    fixture number(x: u32) -> u32 {
+        params {
+            vec![1, 2, 3].into_iter()
+        }
+        setup(&mut self) {
+            *self.x
+        }
+    }
+
+    fixture tens(x: u32) -> u32 {
+        params {
+            vec![10, 20].into_iter()
+        }
+        setup(&mut self) {
+            *self.x
+        }
+    }
+
+    #[combine(zip)]
+    test zip_in_suite(number, tens) {
+        assert_eq!(tens.val, number.val * 10);
+    }
+
+    #[per_case]
+    #[combine(zip)]
+    test zip_per_case(number, tens) {
+        assert_eq!(tens.val, number.val * 10);
+    }
+
+    test generates_one_test_per_zipped_case() {
+        // the third case of `number` has no counterpart in `tens`
+        assert_eq!(
+            generated_tests("zipped_suite::zip_per_case"),
+            ["zipped_suite::zip_per_case::case_0_x_1_x_10", "zipped_suite::zip_per_case::case_1_x_2_x_20"]
+        );
+        assert_eq!(zip_per_case::__GALVANIC_CASES, 2);
+    }
+}
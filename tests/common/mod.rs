@@ -0,0 +1,65 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Helpers shared by the integration tests, each test binary uses only some of them.
+#![allow(dead_code)]
+
+use std::process::{Command, Output};
+
+/// Runs a single ignored test of this test binary.
+pub fn run_ignored_test(name: &str) -> Output {
+    run_ignored_test_with(name, |_| {})
+}
+
+/// Runs a single ignored test of this test binary after adjusting its command, e.g., its environment.
+pub fn run_ignored_test_with<C: FnOnce(&mut Command)>(name: &str, configure: C) -> Output {
+    let mut command = Command::new(std::env::current_exe().unwrap());
+    command.args(["--ignored", "--exact", name]);
+    configure(&mut command);
+    command.output().expect("Could not run the test binary")
+}
+
+/// Returns the lines of the summary of the failed parameterisations, i.e., of the message of the final panic.
+pub fn summary(stdout: &str) -> Vec<&str> {
+    let start = stdout
+        .rfind("Some parameterised test cases")
+        .unwrap_or_else(|| panic!("No summary in output: {}", stdout));
+    let mut lines = stdout[start..].lines();
+    let heading = lines.next().unwrap();
+    // the heading is separated from the counts by an empty line, the message ends with the next one
+    let cases = lines
+        .skip(1)
+        .take_while(|line| !line.is_empty() && !line.starts_with("note: ") && !line.starts_with("stack backtrace:"));
+    Some(heading).into_iter().chain(cases).collect()
+}
+
+/// Lists the names of all tests of this test binary.
+pub fn list_tests() -> Vec<String> {
+    let output = Command::new(std::env::current_exe().unwrap())
+        .arg("--list")
+        .output()
+        .expect("Could not run the test binary");
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.strip_suffix(": test"))
+        .map(String::from)
+        .collect()
+}
+
+/// Lists the names of the tests generated for the cases of the `test` with the given path, e.g., by `#[per_case]`.
+pub fn generated_tests(test: &str) -> Vec<String> {
+    let prefix = format!("{}::", test);
+    list_tests().into_iter().filter(|name| name.starts_with(&prefix)).collect()
+}
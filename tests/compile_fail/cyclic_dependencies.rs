@@ -0,0 +1,15 @@
+use galvanic_test::fixture;
+
+fixture!( chicken(egg) -> u32 {
+    setup(&mut self) {
+        *egg
+    }
+});
+
+fixture!( egg(chicken) -> u32 {
+    setup(&mut self) {
+        *chicken
+    }
+});
+
+fn main() {}
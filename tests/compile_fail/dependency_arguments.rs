@@ -0,0 +1,15 @@
+use galvanic_test::fixture;
+
+fixture!( temp_dir(name: String) -> String {
+    setup(&mut self) {
+        format!("/tmp/{}", self.name)
+    }
+});
+
+fixture!( db_conn(temp_dir) -> String {
+    setup(&mut self) {
+        format!("{}/db", temp_dir)
+    }
+});
+
+fn main() {}
@@ -0,0 +1,9 @@
+use galvanic_test::fixture;
+
+fixture!( number(value u32) -> u32 {
+    setup(&mut self) {
+        1
+    }
+});
+
+fn main() {}
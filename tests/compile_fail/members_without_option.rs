@@ -0,0 +1,11 @@
+// `test_suite!` only expands its contents with `cfg(test)`, so they are passed to its internal macro directly
+galvanic_test::__test_suite_int! { @int [] []
+    fixture connection() -> String {
+        members { log: Vec<String> }
+        setup(&mut self) {
+            String::from("connected")
+        }
+    }
+}
+
+fn main() {}
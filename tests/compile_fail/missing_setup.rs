@@ -0,0 +1,9 @@
+use galvanic_test::fixture;
+
+fixture!( number(value: u32) -> u32 {
+    params {
+        vec![1, 2].into_iter()
+    }
+});
+
+fn main() {}
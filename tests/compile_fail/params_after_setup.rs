@@ -0,0 +1,13 @@
+// `test_suite!` only expands its contents with `cfg(test)`, so they are passed to its internal macro directly
+galvanic_test::__test_suite_int! { @int [] []
+    fixture number(value: u32) -> u32 {
+        setup(&mut self) {
+            *self.value
+        }
+        params {
+            vec![1, 2].into_iter()
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use galvanic_test::fixture;
+
+fixture!( number() -> u32 {
+    params {
+        vec![1, 2].into_iter()
+    }
+    setup(&mut self) {
+        1
+    }
+});
+
+fn main() {}
@@ -0,0 +1,9 @@
+use galvanic_test::fixture;
+
+fixture!( number() -> u32 {
+    setup(&self) {
+        1
+    }
+});
+
+fn main() {}
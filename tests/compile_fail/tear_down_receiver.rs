@@ -0,0 +1,10 @@
+use galvanic_test::fixture;
+
+fixture!( number() -> u32 {
+    setup(&mut self) {
+        1
+    }
+    tear_down(&mut self) {}
+});
+
+fn main() {}
@@ -0,0 +1,11 @@
+// `test_suite!` only expands its contents with `cfg(test)`, so they are passed to its internal macro directly
+galvanic_test::__test_suite_int! { @int [] []
+    fixture number() -> u32 {
+        setup(&mut self) {
+            1
+        }
+        teardown(&self) {}
+    }
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+// `test_suite!` only expands its contents with `cfg(test)`, so they are passed to its internal macro directly
+galvanic_test::__test_suite_int! { @int [] []
+    test adds_numbers {
+        assert_eq!(1 + 1, 2);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,121 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use galvanic_test::{fixture, test, TestFixture};
+use std::process::Output;
+
+/// Runs a single ignored test of this test binary, showing its output.
+fn run_ignored_test(name: &str) -> Output {
+    common::run_ignored_test_with(name, |command| {
+        command.args(["--nocapture", "--test-threads=1"]);
+    })
+}
+
+#[derive(Debug, TestFixture)]
+#[fixture(setup = "connect", tear_down = "disconnect", params = "urls", value = "String")]
+struct Database<'param> {
+    #[fixture(param)]
+    url: &'param String,
+    #[fixture(member)]
+    connections: Vec<String>,
+}
+
+impl<'param> Database<'param> {
+    fn urls() -> Vec<String> {
+        vec![String::from("db://first"), String::from("db://second")]
+    }
+
+    fn connect(&mut self) -> String {
+        self.connections.push(self.url.clone());
+        format!("connected to {}", self.url)
+    }
+
+    fn disconnect(&self) {
+        println!("disconnected from {}", self.connections.join(", "));
+    }
+}
+
+#[derive(Debug, TestFixture)]
+#[fixture(setup = "start", value = "u32")]
+struct Counter {}
+
+impl Counter {
+    fn start(&mut self) -> u32 {
+        1
+    }
+}
+
+#[derive(Debug, TestFixture)]
+#[fixture(setup = "add")]
+struct Sum<'a> {
+    #[fixture(param)]
+    x: &'a i32,
+    #[fixture(param)]
+    y: &'a i32,
+    #[fixture(member)]
+    sum: Option<i32>,
+}
+
+impl<'a> Sum<'a> {
+    fn add(&mut self) {
+        self.sum = Some(self.x + self.y);
+    }
+}
+
+fixture!( counted_twice(Counter) -> u32 {
+    setup(&mut self) {
+        *Counter * 2
+    }
+});
+
+test!( inject_derived_fixture_with_cases | Database | {
+    assert!(Database.val.starts_with("connected to db://"));
+    assert_eq!(Database.params.connections, vec![Database.params.url.clone()]);
+});
+
+test!( inject_derived_fixture_without_parameters | Counter | {
+    assert_eq!(Counter.val, 1);
+});
+
+test!( inject_derived_fixture_with_arguments | Sum(2, 3) | {
+    assert_eq!(Sum.params.sum, Some(5));
+});
+
+test!( depend_on_derived_fixture | counted_twice | {
+    assert_eq!(counted_twice.val, 2);
+});
+
+#[test]
+fn should_list_the_parameters_of_a_derived_fixture() {
+    let urls: Vec<String> = Database::parameters().unwrap().collect();
+    assert_eq!(urls, vec!["db://first", "db://second"]);
+    assert!(Sum::parameters().is_none());
+}
+
+#[test]
+fn should_tear_down_derived_fixtures() {
+    let output = run_ignored_test("tear_down_database");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("disconnected from db://first\n"), "{}", stdout);
+    assert!(stdout.contains("disconnected from db://second\n"), "{}", stdout);
+}
+
+test!(
+    #[ignore]
+    tear_down_database | Database | {}
+);
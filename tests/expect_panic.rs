@@ -0,0 +1,160 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use common::{run_ignored_test, summary};
+use galvanic_test::{fixture, test, test_suite, TestFixture};
+
+const ITEMS: [u32; 3] = [1, 2, 3];
+
+fixture!( past_the_end(offset: usize) -> usize {
+    params {
+        vec![0, 1, 2].into_iter()
+    }
+    setup(&mut self) {
+        ITEMS.len() + *self.offset
+    }
+});
+
+fixture!( step(size: usize) -> usize {
+    params {
+        vec![1, 2].into_iter()
+    }
+    setup(&mut self) {
+        *self.size
+    }
+});
+
+fixture!( index(i: usize) -> usize {
+    params {
+        vec![0, 3, 1].into_iter()
+    }
+    setup(&mut self) {
+        *self.i
+    }
+});
+
+test!(
+    #[expect_panic("index out of bounds")]
+    expect_every_case_to_panic | past_the_end | {
+        let _ = ITEMS[past_the_end.val];
+    }
+);
+
+test!(
+    #[expect_panic]
+    expect_any_panic | past_the_end | {
+        ITEMS.get(past_the_end.val).unwrap();
+    }
+);
+
+test!(
+    #[combine(zip)]
+    #[expect_panic("index out of bounds")]
+    combine_with_expected_panic | past_the_end, step | {
+        // only the zipped cases are past the end, e.g., not `past_the_end { offset: 0 }, step { size: 2 }`
+        let _ = ITEMS[past_the_end.val - step.val + 1];
+    }
+);
+
+test!(
+    #[expect_panic("not implemented")]
+    expect_panic_without_fixtures {
+        unimplemented!()
+    }
+);
+
+test!(
+    #[expect_panic("no items")]
+    expect_panic_without_fixtures_returning_result -> Result<(), String> {
+        Err(String::from("no items"))
+    }
+);
+
+test!(
+    #[ignore]
+    #[expect_panic("index out of bounds")]
+    fail_for_cases_without_expected_panic | index | {
+        let item = ITEMS[index.val];
+        assert!(item != 2, "{} is not accepted", item);
+    }
+);
+
+#[test]
+fn should_report_cases_without_expected_panic() {
+    let output = run_ignored_test("fail_for_cases_without_expected_panic");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        summary(&stdout),
+        [
+            "Some parameterised test cases failed",
+            "1 of 3 parameterised test cases passed, 2 failed:",
+            "  - index { i: 0 }",
+            "    did not panic, expected a panic containing \"index out of bounds\"",
+            "  - index { i: 1 }",
+            "    panicked with an unexpected message, expected a panic containing \"index out of bounds\": 2 is not accepted",
+        ]
+    );
+}
+
+test_suite! {
+    name expect_panic_in_suite;
+    use super::ITEMS;
+
+    fixture capacity(n: i64) -> usize {
+        params {
+            vec![-1, -2].into_iter()
+        }
+        setup(&mut self) {
+            *self.n as usize
+        }
+    }
+
+    #[expect_panic("capacity overflow")]
+    test every_case_panics(capacity) {
+        let _ = Vec::<u64>::with_capacity(capacity.val);
+    }
+
+    #[per_case]
+    #[expect_panic("capacity overflow")]
+    test every_generated_case_panics(capacity) {
+        let _ = Vec::<u64>::with_capacity(capacity.val);
+    }
+
+    #[expect_panic("index out of bounds")]
+    test panic_without_fixtures() {
+        let _ = ITEMS[ITEMS.len()];
+    }
+
+    #[ignore]
+    #[expect_panic("index out of bounds")]
+    test fail_without_fixtures_and_expected_panic() {
+        let _ = ITEMS[0];
+    }
+}
+
+#[test]
+fn should_fail_suite_test_without_fixtures_and_expected_panic() {
+    let output = run_ignored_test("expect_panic_in_suite::fail_without_fixtures_and_expected_panic");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("note: test did not panic as expected"),
+        "The test without fixtures passed: {}",
+        stdout
+    );
+}
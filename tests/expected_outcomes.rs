@@ -0,0 +1,139 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use common::{run_ignored_test, summary};
+use galvanic_test::{fixture, test, test_suite, Case, Expectation, TestFixture};
+
+fixture!( quotient(x: i32, y: i32) -> i32 {
+    params {
+        case "exact" => (12, 3),
+        #[should_panic("attempt to divide by zero")]
+        case "by zero" => (1, 0),
+        #[xfail]
+        case "with remainder" => (7, 2),
+        #[skip("takes too long")]
+        case "slow" => (i32::MAX, 1),
+    }
+    setup(&mut self) {
+        self.x / self.y
+    }
+});
+
+fixture!( wrong_expectations(x: i32) -> i32 {
+    params {
+        #[xfail]
+        case "passing" => 1,
+        #[should_panic("overflow")]
+        case "wrong panic" => 0,
+        #[skip("not relevant")]
+        case "skipped" => 2,
+    }
+    setup(&mut self) {
+        *self.x
+    }
+});
+
+test!(
+    treat_expected_outcomes_as_passed | quotient | {
+        assert_ne!(quotient.params.x, &i32::MAX, "skipped cases are not executed");
+        assert_eq!(quotient.val * quotient.params.y, *quotient.params.x);
+    }
+);
+
+test!(
+    #[ignore]
+    fail_for_unexpected_outcomes | wrong_expectations | {
+        assert!(wrong_expectations.val != 0, "zero is not accepted");
+    }
+);
+
+test!(
+    #[expect_panic("not accepted")]
+    prefer_case_expectation_to_expected_panic | wrong_expectations[0] | {
+        panic!("{} is not expected", wrong_expectations.val);
+    }
+);
+
+test!(
+    skip_combinations_with_skipped_cases | quotient, wrong_expectations[2] | {
+        panic!("All combinations are skipped");
+    }
+);
+
+#[test]
+fn should_annotate_cases_with_expectations() {
+    let expectations: Vec<_> = quotient::cases().unwrap().map(|case| case.expectation().clone()).collect();
+    assert_eq!(
+        expectations,
+        vec![
+            Expectation::Pass,
+            Expectation::Panic(String::from("attempt to divide by zero")),
+            Expectation::Fail,
+            Expectation::Skip(String::from("takes too long")),
+        ]
+    );
+    assert_eq!(Case::new(1).skip("reason").expectation(), &Expectation::Skip(String::from("reason")));
+}
+
+#[test]
+fn should_combine_expectations() {
+    let skip = Expectation::Skip(String::from("reason"));
+    let panic = Expectation::Panic(String::from("message"));
+    assert_eq!(Expectation::of_combination(&[&Expectation::Pass, &Expectation::Pass]), Expectation::Pass);
+    assert_eq!(Expectation::of_combination(&[&Expectation::Fail, &panic]), panic);
+    assert_eq!(Expectation::of_combination(&[&panic, &skip, &Expectation::Fail]), skip);
+}
+
+#[test]
+fn should_report_unexpected_outcomes() {
+    let output = run_ignored_test("fail_for_unexpected_outcomes");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        summary(&stdout),
+        [
+            "Some parameterised test cases failed",
+            "0 of 3 parameterised test cases passed, 2 failed, 1 skipped:",
+            "  - \"passing\" => wrong_expectations { x: 1 }",
+            "    XPASS: passed although it is expected to fail",
+            "  - \"wrong panic\" => wrong_expectations { x: 0 }",
+            "    panicked with an unexpected message, expected a panic containing \"overflow\": zero is not accepted",
+            "  - \"skipped\" => wrong_expectations { x: 2 }",
+            "    skipped: not relevant",
+        ]
+    );
+}
+
+test_suite! {
+    name expected_outcomes_in_suite;
+
+    fixture sign(x: i32) -> i32 {
+        params {
+            case "positive" => 1,
+            #[xfail]
+            case "negative" => -1,
+        }
+        setup(&mut self) {
+            *self.x
+        }
+    }
+
+    #[per_case]
+    test positive_sign(sign) {
+        assert!(sign.val > 0);
+    }
+}
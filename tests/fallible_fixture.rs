@@ -0,0 +1,157 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod basic {
+    use galvanic_test::{fixture, test, TestFixture};
+
+    fixture!( parsed_number(input: &'static str) -> Result<i32, std::num::ParseIntError> {
+        params {
+            vec!["1", "22", "333"].into_iter()
+        }
+        setup(&mut self) {
+            self.input.parse()
+        }
+    });
+
+    test!(
+        inject_value_of_fallible_fixture | parsed_number | {
+            assert_eq!(parsed_number.val.to_string(), *parsed_number.params.input);
+        }
+    );
+
+    test!(
+        #[should_panic(expected = "Some fixtures of the test could not be set up")]
+        report_failed_setup_as_fixture_error | parsed_number("not a number") | {
+            unreachable!("The test must not be executed if its fixtures cannot be set up");
+        }
+    );
+
+    #[test]
+    fn should_describe_fixture_and_parameterisation_in_fixture_error() {
+        let mut fixture = parsed_number::new(&"x");
+        let error = fixture.try_setup().err().expect("The setup should have failed");
+        assert_eq!(error.fixture(), "parsed_number");
+        assert_eq!(error.parameterisation(), "parsed_number { input: \"x\" }");
+        assert_eq!(
+            error.to_string(),
+            "The fixture `parsed_number` could not be set up with the parameterisation \
+             parsed_number { input: \"x\" }: ParseIntError { kind: InvalidDigit }"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The fixture `parsed_number` could not be set up")]
+    fn should_panic_if_fallible_fixture_is_set_up_directly() {
+        let mut fixture = parsed_number::new(&"x");
+        fixture.setup();
+    }
+}
+
+mod tear_down {
+    use galvanic_test::{fixture, test, TestFixture};
+    use std::cell::RefCell;
+
+    thread_local! {
+        static EVENTS: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn record(event: &'static str) {
+        EVENTS.with(|events| events.borrow_mut().push(event));
+    }
+
+    fixture!( connection() -> &'static str {
+        setup(&mut self) {
+            record("setup connection");
+            "connection"
+        }
+        tear_down(&self) {
+            record("tear down connection");
+        }
+    });
+
+    fixture!( broken_fixture() -> Result<(), String> {
+        setup(&mut self) {
+            record("setup broken_fixture");
+            Err(String::from("broken"))
+        }
+        tear_down(&self) {
+            record("tear down broken_fixture");
+        }
+    });
+
+    fixture!( dependent_fixture(broken_fixture) -> () {
+        setup(&mut self) {
+            record("setup dependent_fixture");
+        }
+    });
+
+    test!(
+        #[should_panic(expected = "Some fixtures of the test could not be set up")]
+        fail_with_fixture_error | connection, broken_fixture | { }
+    );
+
+    test!(
+        #[should_panic(expected = "Some fixtures of the test could not be set up")]
+        fail_with_fixture_error_of_dependency | dependent_fixture | { }
+    );
+
+    #[test]
+    fn should_tear_down_fixtures_set_up_before_the_failed_one() {
+        assert!(std::panic::catch_unwind(fail_with_fixture_error).is_err());
+        EVENTS.with(|events| {
+            assert_eq!(
+                *events.borrow(),
+                vec!["setup connection", "setup broken_fixture", "tear down connection"]
+            );
+        });
+    }
+
+    #[test]
+    fn should_not_set_up_fixture_whose_dependency_failed() {
+        assert!(std::panic::catch_unwind(fail_with_fixture_error_of_dependency).is_err());
+        EVENTS.with(|events| {
+            assert_eq!(*events.borrow(), vec!["setup broken_fixture"]);
+        });
+    }
+}
+
+mod fallible_fixtures_in_test_suite {
+    use galvanic_test::test_suite;
+
+    test_suite! {
+        fixture config_value(raw: String) -> Result<u16, std::num::ParseIntError> {
+            params {
+                vec![String::from("80"), String::from("8080")].into_iter()
+            }
+            setup(&mut self) {
+                self.raw.parse()
+            }
+        }
+
+        scope suite fixture port() -> Result<u16, String> {
+            setup(&mut self) {
+                Ok(8080)
+            }
+        }
+
+        test inject_fallible_fixture(config_value) {
+            assert_eq!(config_value.val.to_string(), *config_value.params.raw);
+        }
+
+        test inject_fallible_shared_fixture(port) {
+            assert_eq!(*port.val, 8080);
+        }
+    }
+}
@@ -0,0 +1,199 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod shared_dependency {
+    use galvanic_test::{fixture, test, TestFixture};
+    use std::cell::RefCell;
+
+    thread_local! {
+        static EVENTS: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn record(event: &'static str) {
+        EVENTS.with(|events| events.borrow_mut().push(event));
+    }
+
+    fn recorded() -> Vec<&'static str> {
+        EVENTS.with(|events| events.borrow().clone())
+    }
+
+    fixture!( base() -> i32 {
+        setup(&mut self) {
+            record("setup base");
+            21
+        }
+        tear_down(&self) {
+            record("tear down base");
+        }
+    });
+
+    fixture!( doubled(base) -> i32 {
+        setup(&mut self) {
+            record("setup doubled");
+            *base * 2
+        }
+        tear_down(&self) {
+            assert_eq!(*base, 21);
+            record("tear down doubled");
+        }
+    });
+
+    fixture!( offset(base, x: i32) -> i32 {
+        params {
+            vec![1, 2].into_iter()
+        }
+        setup(&mut self) {
+            record("setup offset");
+            *base + self.x
+        }
+    });
+
+    fixture!( quadrupled(doubled) -> i32 {
+        setup(&mut self) {
+            *doubled * 2
+        }
+    });
+
+    test!(
+        inject_fixtures_sharing_a_dependency | doubled, offset | {
+            assert_eq!(doubled.val, 42);
+            assert_eq!(offset.val, 21 + offset.params.x);
+            let setups = recorded().into_iter().filter(|&e| e == "setup base").count();
+            assert_eq!(setups, *offset.params.x as usize);
+        }
+    );
+
+    test!(
+        inject_fixture_with_transitive_dependency | quadrupled | {
+            assert_eq!(quadrupled.val, 84);
+        }
+    );
+
+    #[test]
+    fn should_tear_down_dependencies_in_reverse_order() {
+        {
+            let dependencies = galvanic_test::Dependencies::new();
+            let mut fixture = quadrupled::new(&());
+            fixture.resolve_dependencies(&dependencies).unwrap();
+            assert_eq!(fixture.setup().val, 84);
+        }
+        assert_eq!(
+            recorded(),
+            vec!["setup base", "setup doubled", "tear down doubled", "tear down base"]
+        );
+    }
+
+    test!(
+        share_fixture_injected_along_with_its_dependent | doubled, base | {
+            assert_eq!(base.val * 2, doubled.val);
+            assert_eq!(recorded(), vec!["setup base", "setup doubled"]);
+        }
+    );
+
+    #[test]
+    fn should_tear_down_shared_fixture_once_after_its_dependent() {
+        share_fixture_injected_along_with_its_dependent();
+        assert_eq!(
+            recorded(),
+            vec!["setup base", "setup doubled", "tear down doubled", "tear down base"]
+        );
+    }
+
+    test!(
+        share_fixture_with_dependencies_injected_along_with_its_dependent | doubled, quadrupled | {
+            assert_eq!(doubled.val * 2, quadrupled.val);
+        }
+    );
+
+    #[test]
+    fn should_tear_down_shared_fixture_with_dependencies_once() {
+        share_fixture_with_dependencies_injected_along_with_its_dependent();
+        assert_eq!(
+            recorded(),
+            vec!["setup base", "setup doubled", "tear down doubled", "tear down base"]
+        );
+    }
+
+    pub struct Connection;
+
+    fixture!( connection() -> Connection {
+        setup(&mut self) {
+            Connection
+        }
+    });
+
+    fixture!( session(connection) -> i32 {
+        setup(&mut self) {
+            let Connection = *connection;
+            1
+        }
+    });
+
+    test!(
+        #[should_panic(expected = "The fixture `connection` is injected into the test and is a dependency of another fixture")]
+        reject_uncloneable_fixture_injected_along_with_its_dependent | connection, session | {
+            let Connection = connection.val;
+            assert_eq!(session.val, 1);
+        }
+    );
+
+    test!(
+        tear_down_injected_fixture_before_its_dependency | doubled | {
+            assert_eq!(doubled.val, 42);
+        }
+    );
+
+    #[test]
+    fn should_tear_down_injected_fixtures_before_their_dependencies() {
+        tear_down_injected_fixture_before_its_dependency();
+        assert_eq!(
+            recorded(),
+            vec!["setup base", "setup doubled", "tear down doubled", "tear down base"]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The dependency `base` has not been resolved.")]
+    fn should_refuse_to_set_up_fixture_with_unresolved_dependency() {
+        let mut fixture = doubled::new(&());
+        fixture.setup();
+    }
+}
+
+mod dependencies_in_test_suite {
+    use galvanic_test::test_suite;
+
+    test_suite! {
+        fixture greeting() -> String {
+            setup(&mut self) {
+                String::from("Hello")
+            }
+        }
+
+        fixture personal_greeting(greeting, name: String) -> String {
+            params {
+                vec![String::from("Alice"), String::from("Bob")].into_iter()
+            }
+            setup(&mut self) {
+                format!("{} {}", greeting, self.name)
+            }
+        }
+
+        test inject_fixture_with_dependency(personal_greeting) {
+            let expected = format!("Hello {}", personal_greeting.params.name);
+            assert_eq!(personal_greeting.val, expected);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use galvanic_test::generate::{vec, Just, Rng, Strategy};
+use galvanic_test::{fixture, test, test_suite, TestFixture};
+use std::process::Output;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Runs a single ignored test of this test binary with the given environment.
+fn run_ignored_test(name: &str, env: &[(&str, &str)]) -> Output {
+    common::run_ignored_test_with(name, |command| {
+        command.env_remove("GALVANIC_SEED").envs(env.iter().cloned());
+    })
+}
+
+fixture!( pair(x: i32, y: u8) -> (i32, u8) {
+    params generate(n = 50) {
+        (any::<i32>(), 0..10u8)
+    }
+    setup(&mut self) {
+        (*self.x, *self.y)
+    }
+});
+
+fixture!( seeded(x: u64) -> u64 {
+    params generate(n = 20, seed = 42) {
+        any::<u64>()
+    }
+    setup(&mut self) {
+        *self.x
+    }
+});
+
+static PAIRS: AtomicUsize = AtomicUsize::new(0);
+
+test!( use_generated_params | pair | {
+    PAIRS.fetch_add(1, Ordering::SeqCst);
+    assert!(pair.val.1 < 10);
+});
+
+#[test]
+fn should_generate_the_requested_number_of_cases() {
+    assert_eq!(pair::cases().unwrap().count(), 50);
+    assert_eq!(seeded::cases().unwrap().count(), 20);
+}
+
+#[test]
+fn should_generate_the_same_params_for_the_same_seed() {
+    let first: Vec<_> = seeded::parameters().unwrap().collect();
+    let second: Vec<_> = seeded::parameters().unwrap().collect();
+    assert_eq!(first, second);
+    assert!(first.iter().any(|x| *x != first[0]));
+}
+
+#[test]
+fn should_generate_the_same_params_within_a_run() {
+    let first: Vec<_> = pair::parameters().unwrap().collect();
+    let second: Vec<_> = pair::parameters().unwrap().collect();
+    assert_eq!(first, second);
+}
+
+#[test]
+fn should_remember_the_seed_of_generated_cases() {
+    assert!(seeded::cases().unwrap().all(|case| case.seed() == Some(42)));
+}
+
+#[test]
+fn should_generate_values_within_ranges() {
+    let mut rng = Rng::new(1);
+    for _ in 0..1000 {
+        assert!((-5..5i8).generate(&mut rng) < 5);
+        assert!((-5..=5i64).generate(&mut rng) >= -5);
+        assert!((0.5..1.0f64).generate(&mut rng) >= 0.5);
+        let values = vec(Just('a'), 2..4).generate(&mut rng);
+        assert!(values.len() >= 2 && values.len() < 4);
+    }
+    // the full range of a type does not overflow
+    (u64::MIN..=u64::MAX).generate(&mut rng);
+    (i8::MIN..=i8::MAX).generate(&mut rng);
+}
+
+#[test]
+fn should_report_the_seed_of_a_failing_test() {
+    let output = run_ignored_test("fail_with_generated_params", &[]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(
+        stdout.contains("Some parameters were generated with the seed 42. Set GALVANIC_SEED=42 to replay the test."),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn should_replay_the_seed_given_by_the_environment() {
+    let output = run_ignored_test("fail_with_generated_params", &[("GALVANIC_SEED", "1234")]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(stdout.contains("Set GALVANIC_SEED=1234 to replay the test."), "{}", stdout);
+}
+
+test!(
+    #[ignore]
+    fail_with_generated_params | seeded | {
+        assert!(seeded.val % 2 == 0, "odd");
+    }
+);
+
+test_suite! {
+    name generated_in_suite;
+
+    fixture word(text: String, repetitions: usize) -> String {
+        params generate(n = 10) {
+            (any::<String>(), select(vec![1, 2, 3]))
+        }
+        setup(&mut self) {
+            self.text.repeat(*self.repetitions)
+        }
+    }
+
+    test generated_suite_params(word) {
+        assert_eq!(word.val.len() % word.params.text.len().max(1), 0);
+    }
+}
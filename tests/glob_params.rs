@@ -0,0 +1,82 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use common::run_ignored_test;
+use galvanic_test::{fixture, test, TestFixture};
+use std::fs;
+use std::path::PathBuf;
+
+fixture!( corpus_file(path: PathBuf) -> String {
+    params glob("tests/data/corpus/*.input")
+    setup(&mut self) {
+        fs::read_to_string(self.path).unwrap()
+    }
+});
+
+fixture!( no_file(path: PathBuf) -> () {
+    params glob("tests/data/corpus/*.missing")
+    setup(&mut self) {}
+});
+
+fixture!( invalid_pattern(path: PathBuf) -> () {
+    params glob("tests/data/corpus/[.input")
+    setup(&mut self) {}
+});
+
+test!( read_each_corpus_file | corpus_file | {
+    assert!(corpus_file.params.path.is_absolute());
+    assert!(corpus_file.val.len() < 100);
+});
+
+#[test]
+fn should_create_a_case_per_matching_path() {
+    let ids: Vec<_> = corpus_file::cases().unwrap().map(|case| String::from(case.label().unwrap())).collect();
+    assert_eq!(
+        ids,
+        vec![
+            "tests/data/corpus/empty.input",
+            "tests/data/corpus/greeting.input",
+            "tests/data/corpus/numbers.input"
+        ]
+    );
+}
+
+#[test]
+fn should_describe_cases_by_their_path() {
+    let output = run_ignored_test("fail_for_empty_file");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(stdout.contains("2 of 3 parameterised test cases passed, 1 failed:"), "{}", stdout);
+    assert!(stdout.contains("  - tests/data/corpus/empty.input\n    panicked: empty"), "{}", stdout);
+}
+
+test!(
+    #[ignore]
+    fail_for_empty_file | corpus_file | {
+        assert!(!corpus_file.val.is_empty(), "empty");
+    }
+);
+
+test!(
+    #[should_panic(expected = "The glob pattern `tests/data/corpus/*.missing` does not match any path")]
+    fail_if_no_path_matches | no_file | {}
+);
+
+test!(
+    #[should_panic(expected = "The glob pattern `tests/data/corpus/[.input` is invalid")]
+    fail_for_invalid_pattern | invalid_pattern | {}
+);
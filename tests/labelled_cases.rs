@@ -0,0 +1,133 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use std::process::Output;
+
+/// Runs a single ignored test of this test binary.
+fn run_ignored_test(name: &str, case_filter: Option<&str>) -> Output {
+    common::run_ignored_test_with(name, |command| {
+        command.arg("--nocapture");
+        if let Some(filter) = case_filter {
+            command.env("GALVANIC_CASE", filter);
+        }
+    })
+}
+
+mod labelled {
+    use super::run_ignored_test;
+    use galvanic_test::{fixture, test, TestFixture};
+
+    fixture!( checked_product(x: u32, y: u32) -> Option<u32> {
+        params {
+            case "empty" => (0, 0),
+            case "overflow" => (u32::MAX, 2),
+        }
+        setup(&mut self) {
+            self.x.checked_mul(*self.y)
+        }
+    });
+
+    fixture!( squared(x: i32) -> i32 {
+        params labelled {
+            vec![("one", 1), ("two", 2)].into_iter()
+        }
+        setup(&mut self) {
+            self.x * self.x
+        }
+    });
+
+    test!(
+        inject_labelled_cases | checked_product | {
+            assert_eq!(checked_product.val, checked_product.params.x.checked_mul(*checked_product.params.y));
+        }
+    );
+
+    test!(
+        inject_cases_labelled_by_iterator | squared | {
+            assert_eq!(squared.val, squared.params.x * squared.params.x);
+        }
+    );
+
+    test!(
+        #[ignore]
+        fail_for_overflow | checked_product | {
+            assert!(checked_product.val.is_some());
+        }
+    );
+
+    #[test]
+    fn should_list_cases_with_labels() {
+        let cases: Vec<_> = checked_product::cases().unwrap().collect();
+        assert_eq!(cases[0].label(), Some("empty"));
+        assert_eq!(*cases[1].params(), (u32::MAX, 2));
+        let labels: Vec<_> = squared::cases().unwrap().map(|case| case.label().map(String::from)).collect();
+        assert_eq!(labels, vec![Some(String::from("one")), Some(String::from("two"))]);
+        assert_eq!(squared::parameters().unwrap().collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn should_show_label_of_failed_case() {
+        let output = run_ignored_test("labelled::fail_for_overflow", None);
+        assert!(!output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.contains("\"overflow\" => checked_product { x: 4294967295, y: 2 }"),
+            "Unexpected output: {}",
+            stdout
+        );
+        assert!(!stdout.contains("\"empty\" =>"));
+    }
+
+    #[test]
+    fn should_only_execute_cases_matching_filter() {
+        let output = run_ignored_test("labelled::fail_for_overflow", Some("empty"));
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stdout));
+    }
+}
+
+mod labelled_cases_in_test_suite {
+    use galvanic_test::test_suite;
+
+    test_suite! {
+        use super::super::common::generated_tests;
+
+        fixture bounds(lower: i64, upper: i64) -> i64 {
+            params {
+                case "empty range" => (0, 0),
+                case "negative" => (-5, -1),
+            }
+            setup(&mut self) {
+                self.upper - self.lower
+            }
+        }
+
+        #[per_case]
+        test range_is_not_negative(bounds) {
+            assert!(bounds.val >= 0);
+        }
+
+        test names_per_case_tests_by_label() {
+            assert_eq!(
+                generated_tests("labelled_cases_in_test_suite::__galvanic_test::range_is_not_negative"),
+                [
+                    "labelled_cases_in_test_suite::__galvanic_test::range_is_not_negative::case_0_empty_range",
+                    "labelled_cases_in_test_suite::__galvanic_test::range_is_not_negative::case_1_negative",
+                ]
+            );
+        }
+    }
+}
@@ -0,0 +1,166 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use common::{run_ignored_test, summary};
+use galvanic_test::{fixture, test, test_suite, TestFixture};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Barrier;
+use std::time::Duration;
+
+fixture!( number(value: u32) -> u32 {
+    params {
+        1..9
+    }
+    setup(&mut self) {
+        *self.value
+    }
+});
+
+static BARRIER: Barrier = Barrier::new(2);
+
+fixture!( rendezvous(side: &'static str) -> () {
+    params {
+        vec!["left", "right"].into_iter()
+    }
+    setup(&mut self) {}
+});
+
+test!(
+    #[parallel(threads = 2)]
+    #[timeout(Duration::from_secs(10))]
+    execute_cases_concurrently | rendezvous | {
+        // both cases must be executed at the same time to pass the barrier
+        BARRIER.wait();
+    }
+);
+
+static EXECUTED: AtomicUsize = AtomicUsize::new(0);
+
+test!(
+    #[parallel]
+    execute_cases_in_parallel | number | {
+        EXECUTED.fetch_add(1, Ordering::SeqCst);
+        assert!(number.val < 9);
+    }
+);
+
+#[test]
+fn should_execute_every_parallel_case_once() {
+    let output = run_ignored_test("count_parallel_cases");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(stdout.contains("63 of 64 parameterised test cases passed, 1 failed:"), "{}", stdout);
+    assert!(stdout.contains("executed 64 times"), "{}", stdout);
+}
+
+static COUNTED: AtomicUsize = AtomicUsize::new(0);
+
+test!(
+    #[ignore]
+    #[parallel(threads = 3)]
+    count_parallel_cases | number, number | {
+        // the last case to finish reports the number of executed cases
+        let executed = COUNTED.fetch_add(1, Ordering::SeqCst) + 1;
+        assert!(executed < 8 * 8, "executed {} times", executed);
+    }
+);
+
+#[test]
+fn should_report_all_failed_parallel_cases() {
+    let output = run_ignored_test("fail_parallel_cases");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    let summary = summary(&stdout);
+    assert_eq!(summary[..2], ["Some parameterised test cases failed", "4 of 8 parameterised test cases passed, 4 failed:"]);
+    // the cases are reported in the order they finished
+    let mut failed: Vec<_> = summary[2..].chunks(2).map(|case| case.join("\n")).collect();
+    failed.sort();
+    assert_eq!(
+        failed,
+        [2, 4, 6, 8]
+            .iter()
+            .map(|value| format!("  - number {{ value: {} }}\n    panicked: odd numbers only", value))
+            .collect::<Vec<_>>()
+    );
+}
+
+test!(
+    #[ignore]
+    #[parallel(threads = 4)]
+    fail_parallel_cases | number | {
+        assert!(number.val % 2 == 1, "odd numbers only");
+    }
+);
+
+#[test]
+fn should_enforce_timeouts_of_parallel_cases() {
+    let output = run_ignored_test("time_out_parallel_case");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert_eq!(
+        summary(&stdout),
+        [
+            "Some parameterised test cases failed",
+            "7 of 8 parameterised test cases passed, 1 failed:",
+            "  - number { value: 5 }",
+            "    timed out after 100ms in the test case with the parameterisation number { value: 5 }",
+        ]
+    );
+}
+
+test!(
+    #[ignore]
+    #[parallel(threads = 2)]
+    #[timeout(Duration::from_millis(100))]
+    time_out_parallel_case | number | {
+        if number.val == 5 {
+            std::thread::sleep(Duration::from_secs(10));
+        }
+    }
+);
+
+test!(
+    #[parallel]
+    ignore_parallel_for_test_without_fixtures {
+        assert_eq!(1 + 1, 2);
+    }
+);
+
+test_suite! {
+    name parallel_in_suite;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+    fixture side(name: &'static str) -> &'static str {
+        params {
+            vec!["a", "b", "c"].into_iter()
+        }
+        setup(&mut self) {
+            *self.name
+        }
+    }
+
+    #[parallel(threads = 2)]
+    #[retry(1)]
+    test parallel_suite_test(side) {
+        assert_eq!(side.val.len(), 1);
+        if side.val == "b" {
+            assert!(ATTEMPTS.fetch_add(1, Ordering::SeqCst) > 0);
+        }
+    }
+}
@@ -58,6 +58,7 @@ mod test_setup_teardown_for_each_parameterisation_of_a_single_fixture {
     });
 
     test!(
+        #[allow(static_mut_refs)]
         inject_parameterised_fixtures | counting_fixture | {
             let params = counting_fixture.into_params();
             unsafe {
@@ -102,6 +103,7 @@ mod test_setup_teardown_for_each_parameterisation_of_multiple_fixtures {
     });
 
     test!(
+        #[allow(static_mut_refs)]
         inject_parameterised_fixtures | counting_fixture_1,
         counting_fixture_2 | {
             let params1 = &counting_fixture_1.params;
@@ -0,0 +1,178 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+#[cfg(not(feature = "csv"))]
+mod without_features {
+    use galvanic_test::{fixture, test, TestFixture};
+
+    fixture!( csv_cases(value: u32) -> u32 {
+        params from_file("tests/data/words.csv")
+        setup(&mut self) {
+            *self.value
+        }
+    });
+
+    test!(
+        #[should_panic(expected = "Loading the cases of the file `tests/data/words.csv` requires the `csv` feature of galvanic-test.")]
+        require_feature_for_format | csv_cases | {}
+    );
+}
+
+#[cfg(all(feature = "csv", feature = "serde_json", feature = "toml"))]
+mod formats {
+    use galvanic_test::{fixture, test, TestFixture};
+    use std::process::Output;
+
+    /// Runs a single ignored test of this module.
+    fn run_ignored_test(name: &str) -> Output {
+        crate::common::run_ignored_test(&format!("formats::{}", name))
+    }
+
+    fixture!( csv_division(dividend: u32, divisor: u32, quotient: u32) -> u32 {
+        params from_file("tests/data/division.csv")
+        setup(&mut self) {
+            self.dividend / self.divisor
+        }
+    });
+
+    fixture!( json_division(dividend: u32, divisor: u32, quotient: u32) -> u32 {
+        params from_file("tests/data/division.json")
+        setup(&mut self) {
+            self.dividend / self.divisor
+        }
+    });
+
+    fixture!( toml_division(dividend: u32, divisor: u32, quotient: u32) -> u32 {
+        params from_file("tests/data/division.toml")
+        setup(&mut self) {
+            self.dividend / self.divisor
+        }
+    });
+
+    fixture!( word(text: String) -> usize {
+        params from_file("tests/data/words.csv")
+        setup(&mut self) {
+            self.text.len()
+        }
+    });
+
+    fixture!( quote(text: String, lines: usize) -> usize {
+        params from_file("tests/data/quotes.csv")
+        setup(&mut self) {
+            self.text.lines().count()
+        }
+    });
+
+    fixture!( wrong_division(dividend: u32, divisor: u32, quotient: u32) -> u32 {
+        params from_file("tests/data/wrong_division.csv")
+        setup(&mut self) {
+            self.dividend / self.divisor
+        }
+    });
+
+    fixture!( missing_file(value: u32) -> u32 {
+        params from_file("tests/data/missing.csv")
+        setup(&mut self) {
+            *self.value
+        }
+    });
+
+    test!( divide_cases_from_csv | csv_division | {
+        assert_eq!(csv_division.val, *csv_division.params.quotient);
+    });
+
+    test!( divide_cases_from_json | json_division | {
+        assert_eq!(json_division.val, *json_division.params.quotient);
+    });
+
+    test!( divide_cases_from_toml | toml_division | {
+        assert_eq!(toml_division.val, *toml_division.params.quotient);
+    });
+
+    test!( load_single_parameter | word | {
+        assert!(word.val >= 4);
+    });
+
+    #[test]
+    fn should_load_all_records_of_each_format() {
+        assert_eq!(csv_division::parameters().unwrap().collect::<Vec<_>>(), vec![(6, 3, 2), (9, 3, 3), (10, 5, 2)]);
+        assert_eq!(json_division::parameters().unwrap().count(), 3);
+        assert_eq!(toml_division::parameters().unwrap().count(), 3);
+    }
+
+    test!( count_lines_of_quoted_fields | quote | {
+        assert_eq!(quote.val, *quote.params.lines);
+    });
+
+    /// The labels of the cases of a fixture.
+    fn labels<P>(cases: impl Iterator<Item = galvanic_test::Case<P>>) -> Vec<String> {
+        cases.map(|case| String::from(case.label().unwrap())).collect()
+    }
+
+    #[test]
+    fn should_label_cases_with_the_line_of_their_record() {
+        assert_eq!(
+            labels(csv_division::cases().unwrap()),
+            ["tests/data/division.csv:2", "tests/data/division.csv:3", "tests/data/division.csv:4"]
+        );
+        assert_eq!(
+            labels(json_division::cases().unwrap()),
+            ["tests/data/division.json:2", "tests/data/division.json:3", "tests/data/division.json:4"]
+        );
+        assert_eq!(
+            labels(toml_division::cases().unwrap()),
+            ["tests/data/division.toml:2", "tests/data/division.toml:3", "tests/data/division.toml:4"]
+        );
+    }
+
+    #[test]
+    fn should_label_csv_cases_with_the_line_their_record_starts_on() {
+        // the header is on line 1 and the second record spans lines 3 and 4
+        assert_eq!(
+            labels(quote::cases().unwrap()),
+            ["tests/data/quotes.csv:2", "tests/data/quotes.csv:3", "tests/data/quotes.csv:5"]
+        );
+        assert_eq!(
+            quote::parameters().unwrap().map(|(text, _)| text).collect::<Vec<_>>(),
+            ["a single line", "a field\nspanning two lines", "\"quoted\", on one line"]
+        );
+    }
+
+    #[test]
+    fn should_report_the_record_of_a_failing_case() {
+        let output = run_ignored_test("fail_for_wrong_record");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(!output.status.success());
+        assert!(
+            stdout.contains("  - \"tests/data/wrong_division.csv:3\" => wrong_division { dividend: 7, divisor: 2, quotient: 4 }"),
+            "{}",
+            stdout
+        );
+    }
+
+    test!(
+        #[ignore]
+        fail_for_wrong_record | wrong_division | {
+            assert_eq!(wrong_division.val, *wrong_division.params.quotient);
+        }
+    );
+
+    test!(
+        #[should_panic(expected = "Could not read the cases of the file")]
+        fail_for_missing_file | missing_file | {}
+    );
+}
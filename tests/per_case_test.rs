@@ -0,0 +1,120 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use galvanic_test::test_suite;
+
+test_suite! {
+    name per_case_suite;
+    use super::common::{generated_tests, list_tests};
+
+    fixture product(x: i32, y: i32) -> i32 {
+        params {
+            vec![(1, 4), (-2, 3)].into_iter()
+        }
+        setup(&mut self) {
+            self.x * self.y
+        }
+    }
+
+    fixture greeting(name: String) -> String {
+        params {
+            [String::from("Alice"), String::from("Bob")].to_vec().into_iter()
+        }
+        setup(&mut self) {
+            format!("Hello {}", self.name)
+        }
+    }
+
+    fixture countdown(n: u32) -> u32 {
+        params {
+            vec![3, 2, 1].into_iter().rev()
+        }
+        setup(&mut self) {
+            *self.n
+        }
+    }
+
+    fixture unexpanded(z: u8) -> u8 {
+        params {
+            1..3
+        }
+        setup(&mut self) {
+            *self.z
+        }
+    }
+
+    #[per_case]
+    test multiplies(product) {
+        assert_eq!(product.val, product.params.x * product.params.y);
+    }
+
+    #[per_case]
+    test greets(greeting, product(2, 3)) {
+        assert!(greeting.val.starts_with("Hello"));
+        assert_eq!(product.val, 6);
+    }
+
+    #[per_case]
+    test loops_over_unexpanded_fixture(product, unexpanded) {
+        assert!(unexpanded.val >= 1);
+    }
+
+    #[per_case]
+    test counts_down(countdown) {
+        assert!(countdown.val >= 1);
+    }
+
+    #[per_case]
+    #[should_panic(expected = "Some parameterised test cases failed")]
+    test fails_per_case(product) -> Result<(), String> {
+        Err(format!("{} is not accepted", product.val))
+    }
+
+    test generates_one_test_per_case() {
+        assert_eq!(
+            generated_tests("per_case_suite::multiplies"),
+            ["per_case_suite::multiplies::case_0_x_1_y_4", "per_case_suite::multiplies::case_1_x_neg2_y_3"]
+        );
+        assert_eq!(
+            generated_tests("per_case_suite::greets"),
+            ["per_case_suite::greets::case_0_name_alice", "per_case_suite::greets::case_1_name_bob"]
+        );
+        // the fixtures whose cases are not known statically are iterated over by each generated test
+        assert_eq!(
+            generated_tests("per_case_suite::loops_over_unexpanded_fixture"),
+            [
+                "per_case_suite::loops_over_unexpanded_fixture::case_0_x_1_y_4",
+                "per_case_suite::loops_over_unexpanded_fixture::case_1_x_neg2_y_3",
+            ]
+        );
+        assert_eq!(
+            generated_tests("per_case_suite::fails_per_case"),
+            ["per_case_suite::fails_per_case::case_0_x_1_y_4", "per_case_suite::fails_per_case::case_1_x_neg2_y_3"]
+        );
+        // the reversed cases are not named by the listed values which are not the values of the case
+        assert_eq!(
+            generated_tests("per_case_suite::counts_down"),
+            [
+                "per_case_suite::counts_down::case_0",
+                "per_case_suite::counts_down::case_1",
+                "per_case_suite::counts_down::case_2",
+            ]
+        );
+        assert!(!list_tests().iter().any(|test| test == "per_case_suite::multiplies"));
+        assert_eq!(multiplies::__GALVANIC_CASES, 2);
+    }
+}
@@ -0,0 +1,66 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![cfg(feature = "proptest")]
+
+mod common;
+
+use galvanic_test::{fixture, test, TestFixture};
+use std::process::Output;
+
+/// Runs a single ignored test of this test binary.
+fn run_ignored_test(name: &str) -> Output {
+    common::run_ignored_test_with(name, |command| {
+        command.env_remove("GALVANIC_SEED");
+    })
+}
+
+fixture!( entry(key: i32, name: String) -> String {
+    params generate(n = 20, seed = 3) {
+        from_proptest((proptest::prelude::any::<i32>(), "[a-z]{1,10}"))
+    }
+    setup(&mut self) {
+        format!("{}={}", self.key, self.name)
+    }
+});
+
+test!( use_proptest_params | entry | {
+    assert!(entry.val.contains('='));
+    assert!(entry.params.name.chars().all(|c| c.is_ascii_lowercase()));
+});
+
+#[test]
+fn should_generate_the_same_params_for_the_same_seed() {
+    let first: Vec<_> = entry::parameters().unwrap().collect();
+    let second: Vec<_> = entry::parameters().unwrap().collect();
+    assert_eq!(first.len(), 20);
+    assert_eq!(first, second);
+}
+
+#[test]
+fn should_shrink_with_the_value_trees_of_proptest() {
+    let output = run_ignored_test("fail_for_large_keys");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(stdout.contains("  - entry { key: 1000, name: \"a\" }\n    panicked: too large\n    shrunk in "), "{}", stdout);
+    assert!(stdout.contains("Set GALVANIC_SEED=3 to replay the test."), "{}", stdout);
+}
+
+test!(
+    #[ignore]
+    fail_for_large_keys | entry | {
+        assert!(entry.params.key < &1000, "too large");
+    }
+);
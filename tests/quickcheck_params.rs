@@ -0,0 +1,64 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![cfg(feature = "quickcheck")]
+
+mod common;
+
+use galvanic_test::{fixture, test, TestFixture};
+use std::process::Output;
+
+/// Runs a single ignored test of this test binary.
+fn run_ignored_test(name: &str) -> Output {
+    common::run_ignored_test_with(name, |command| {
+        command.env_remove("GALVANIC_SEED");
+    })
+}
+
+fixture!( numbers(values: Vec<u16>) -> u64 {
+    params generate(n = 20, seed = 4) {
+        from_arbitrary::<Vec<u16>>()
+    }
+    setup(&mut self) {
+        self.values.iter().map(|value| u64::from(*value)).sum()
+    }
+});
+
+test!( use_quickcheck_params | numbers | {
+    assert!(numbers.val <= u64::from(u16::MAX) * numbers.params.values.len() as u64);
+});
+
+#[test]
+fn should_generate_the_same_params_for_the_same_seed() {
+    let first: Vec<_> = numbers::parameters().unwrap().collect();
+    let second: Vec<_> = numbers::parameters().unwrap().collect();
+    assert_eq!(first.len(), 20);
+    assert_eq!(first, second);
+}
+
+#[test]
+fn should_shrink_with_the_shrinkers_of_quickcheck() {
+    let output = run_ignored_test("fail_for_long_vectors");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(stdout.contains("  - numbers { values: [0, 0] }\n    panicked: too long\n    shrunk in "), "{}", stdout);
+}
+
+test!(
+    #[ignore]
+    fail_for_long_vectors | numbers | {
+        assert!(numbers.params.values.len() < 2, "too long");
+    }
+);
@@ -0,0 +1,172 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use common::{run_ignored_test, summary};
+use galvanic_test::{fixture, test, test_suite, TestFixture};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static SETUPS: AtomicUsize = AtomicUsize::new(0);
+static TEAR_DOWNS: AtomicUsize = AtomicUsize::new(0);
+
+fixture!( flaky(failures: usize) -> AtomicUsize {
+    params {
+        vec![0, 2].into_iter()
+    }
+    setup(&mut self) {
+        SETUPS.fetch_add(1, Ordering::SeqCst);
+        AtomicUsize::new(0)
+    }
+    tear_down(&self) {
+        TEAR_DOWNS.fetch_add(1, Ordering::SeqCst);
+    }
+});
+
+static FLAKY_ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+test!(
+    #[retry(2)]
+    pass_on_retry | flaky | {
+        // the fixture is set up again for every attempt
+        assert_eq!(flaky.val.fetch_add(1, Ordering::SeqCst), 0);
+        if *flaky.params.failures > 0 {
+            let attempt = FLAKY_ATTEMPTS.fetch_add(1, Ordering::SeqCst);
+            assert!(attempt >= *flaky.params.failures, "failed attempt {}", attempt);
+        }
+    }
+);
+
+#[test]
+fn should_set_up_fixtures_for_every_attempt() {
+    let output = run_ignored_test("set_up_for_every_attempt");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stdout));
+}
+
+static COUNTED_ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+test!(
+    #[ignore]
+    #[retry(2)]
+    set_up_for_every_attempt | flaky(1) | {
+        if COUNTED_ATTEMPTS.fetch_add(1, Ordering::SeqCst) < 1 {
+            panic!("flaky");
+        }
+        assert_eq!(SETUPS.load(Ordering::SeqCst), 2);
+        assert_eq!(TEAR_DOWNS.load(Ordering::SeqCst), 1);
+    }
+);
+
+test!(
+    #[ignore]
+    #[retry(1)]
+    fail_after_all_retries | flaky | {
+        assert_eq!(*flaky.params.failures, 0, "always failing");
+    }
+);
+
+static PLAIN_ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+test!(
+    #[retry(3)]
+    retry_test_without_fixtures {
+        assert!(PLAIN_ATTEMPTS.fetch_add(1, Ordering::SeqCst) >= 2);
+    }
+);
+
+static RESULT_ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+test!(
+    #[retry(1)]
+    retry_test_returning_error -> Result<(), String> {
+        if RESULT_ATTEMPTS.fetch_add(1, Ordering::SeqCst) == 0 {
+            Err(String::from("first attempt"))
+        } else {
+            Ok(())
+        }
+    }
+);
+
+test!(
+    #[retry(1)]
+    #[should_panic(expected = "always failing")]
+    fail_test_without_fixtures_after_all_retries {
+        panic!("always failing");
+    }
+);
+
+#[test]
+fn should_report_cases_passing_on_retry() {
+    let output = run_ignored_test("report_retries");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert_eq!(
+        summary(&stdout),
+        [
+            "Some parameterised test cases failed",
+            "1 of 2 parameterised test cases passed (1 on retry), 1 failed:",
+            "  - flaky { failures: 2 }",
+            "    panicked: always failing",
+            "  - flaky { failures: 0 }",
+            "    passed on retry 1",
+        ]
+    );
+}
+
+static REPORTED_ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+test!(
+    #[ignore]
+    #[retry(1)]
+    report_retries | flaky | {
+        if *flaky.params.failures == 0 {
+            assert!(REPORTED_ATTEMPTS.fetch_add(1, Ordering::SeqCst) > 0, "flaky");
+        } else {
+            panic!("always failing");
+        }
+    }
+);
+
+#[test]
+fn should_report_failures_after_all_retries() {
+    let output = run_ignored_test("fail_after_all_retries");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    // only the failing case is retried, as often as allowed
+    assert_eq!(stdout.matches("Retrying the test case after the failed attempt").count(), 1, "{}", stdout);
+    assert_eq!(
+        summary(&stdout),
+        [
+            "Some parameterised test cases failed",
+            "1 of 2 parameterised test cases passed, 1 failed:",
+            "  - flaky { failures: 2 }",
+            "    panicked: assertion `left == right` failed: always failing",
+            "      left: 2",
+            "     right: 0",
+        ]
+    );
+}
+
+test_suite! {
+    name retry_in_suite;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+    #[retry(1)]
+    test retry_suite_test() {
+        assert!(ATTEMPTS.fetch_add(1, Ordering::SeqCst) > 0);
+    }
+}
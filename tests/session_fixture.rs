@@ -0,0 +1,86 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![cfg_attr(feature = "galvanic_mock_integration", feature(proc_macro_hygiene))]
+
+#[cfg(feature = "galvanic_mock_integration")]
+extern crate galvanic_mock;
+use galvanic_test::fixture;
+#[cfg(test)]
+use galvanic_test::test_suite;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static SETUP_COUNT: AtomicUsize = AtomicUsize::new(0);
+const TEAR_DOWN_MARKER: &str = "GALVANIC_SESSION_TEAR_DOWN_MARKER";
+
+fixture!( scope session workspace() -> String {
+    setup(&mut self) {
+        SETUP_COUNT.fetch_add(1, Ordering::SeqCst);
+        String::from("/tmp/galvanic-workspace")
+    }
+    tear_down(&self) {
+        if std::env::var_os(TEAR_DOWN_MARKER).is_some() {
+            println!("{}", TEAR_DOWN_MARKER);
+        }
+    }
+});
+
+test_suite! {
+    name first_suite;
+    use super::*;
+
+    test uses_session_fixture(workspace) {
+        assert_eq!(workspace.val.as_str(), "/tmp/galvanic-workspace");
+        assert_eq!(SETUP_COUNT.load(Ordering::SeqCst), 1);
+    }
+}
+
+test_suite! {
+    name second_suite;
+    use super::*;
+
+    test uses_session_fixture(workspace) {
+        assert_eq!(workspace.val.as_str(), "/tmp/galvanic-workspace");
+        assert_eq!(SETUP_COUNT.load(Ordering::SeqCst), 1);
+    }
+
+    test tears_down_session_fixture_at_exit() {
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", "first_suite::uses_session_fixture", "--nocapture"])
+            .env(TEAR_DOWN_MARKER, "1")
+            .output()
+            .expect("Could not run the test binary");
+
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains(TEAR_DOWN_MARKER));
+    }
+}
+
+test_suite! {
+    name suite_local_session_fixture;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    scope session fixture counter() -> AtomicUsize {
+        setup(&mut self) {
+            AtomicUsize::new(0)
+        }
+    }
+
+    test increments_shared_counter(counter) {
+        counter.val.fetch_add(1, Ordering::SeqCst);
+        assert!(counter.val.load(Ordering::SeqCst) >= 1);
+    }
+}
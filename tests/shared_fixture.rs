@@ -0,0 +1,150 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![cfg_attr(feature = "galvanic_mock_integration", feature(proc_macro_hygiene))]
+
+#[cfg(feature = "galvanic_mock_integration")]
+extern crate galvanic_mock;
+#[cfg(test)]
+use galvanic_test::test_suite;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static SETUP_COUNT: AtomicUsize = AtomicUsize::new(0);
+static TEAR_DOWN_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+test_suite! {
+    name suite_scoped_fixture;
+    use super::*;
+
+    scope suite fixture shared_number() -> usize {
+        members {
+            setup_count: Option<usize>
+        }
+        setup(&mut self) {
+            let count = SETUP_COUNT.fetch_add(1, Ordering::SeqCst) + 1;
+            self.setup_count = Some(count);
+            count
+        }
+        tear_down(&self) {
+            assert_eq!(self.setup_count, Some(1));
+            TEAR_DOWN_COUNT.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fixture per_test_number(x: usize) -> usize {
+        params {
+            vec![1, 2, 3].into_iter()
+        }
+        setup(&mut self) {
+            *self.x
+        }
+    }
+
+    test first_test_using_the_fixture(shared_number) {
+        assert_eq!(*shared_number.val, 1);
+        assert_eq!(TEAR_DOWN_COUNT.load(Ordering::SeqCst), 0);
+    }
+
+    test second_test_using_the_fixture(shared_number) {
+        assert_eq!(*shared_number.val, 1);
+        assert_eq!(TEAR_DOWN_COUNT.load(Ordering::SeqCst), 0);
+    }
+
+    test parameterised_test_using_the_fixture(shared_number, per_test_number) {
+        assert_eq!(*shared_number.val, 1);
+        assert!(per_test_number.val <= 3);
+        assert_eq!(SETUP_COUNT.load(Ordering::SeqCst), 1);
+    }
+}
+
+test_suite! {
+    name module_scoped_fixture;
+
+    scope module fixture shared_text() -> String {
+        setup(&mut self) {
+            String::from("shared")
+        }
+    }
+
+    test inject_module_scoped_fixture(shared_text) {
+        assert_eq!(shared_text.val.as_str(), "shared");
+    }
+}
+
+mod suite_tracker {
+    use galvanic_test::{SharedFixture, SuiteTracker};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    static SUITE: SuiteTracker = SuiteTracker::new(&[(&["shared"], 2)]);
+    static SHARED: SharedFixture<i32> = SharedFixture::new("shared");
+    static TORN_DOWN: AtomicBool = AtomicBool::new(false);
+
+    struct FlagOnDrop(&'static AtomicBool);
+
+    impl Drop for FlagOnDrop {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn should_tear_down_shared_fixture_after_the_last_test_of_the_suite() {
+        let first_test = SUITE.enter(&["shared"]);
+        let value = SHARED.get_or_setup(Some(&SUITE), || (42, Box::new(FlagOnDrop(&TORN_DOWN))));
+        assert_eq!(*value, 42);
+        drop(first_test);
+        assert!(!TORN_DOWN.load(Ordering::SeqCst));
+
+        let second_test = SUITE.enter(&["shared"]);
+        let value = SHARED.get_or_setup(Some(&SUITE), || panic!("The fixture must not be set up twice"));
+        assert_eq!(*value, 42);
+        drop(second_test);
+        assert!(TORN_DOWN.load(Ordering::SeqCst));
+    }
+
+    // the second test is expanded into three test functions, e.g., by `#[per_case]`
+    static USAGE_SUITE: SuiteTracker = SuiteTracker::new(&[(&["server"], 1), (&["server", "client"], 3), (&[], 1)]);
+    static SERVER: SharedFixture<&str> = SharedFixture::new("server");
+    static CLIENT: SharedFixture<&str> = SharedFixture::new("client");
+    static DATABASE: SharedFixture<&str> = SharedFixture::new("database");
+    static SERVER_TORN_DOWN: AtomicBool = AtomicBool::new(false);
+    static CLIENT_TORN_DOWN: AtomicBool = AtomicBool::new(false);
+    static DATABASE_TORN_DOWN: AtomicBool = AtomicBool::new(false);
+
+    #[test]
+    fn should_tear_down_shared_fixture_after_the_last_test_injecting_it() {
+        let first_test = USAGE_SUITE.enter(&["server"]);
+        SERVER.get_or_setup(Some(&USAGE_SUITE), || ("server", Box::new(FlagOnDrop(&SERVER_TORN_DOWN))));
+        // a fixture only set up as a dependency of another fixture is not counted by the tests
+        DATABASE.get_or_setup(Some(&USAGE_SUITE), || ("database", Box::new(FlagOnDrop(&DATABASE_TORN_DOWN))));
+        drop(first_test);
+        assert!(!SERVER_TORN_DOWN.load(Ordering::SeqCst));
+
+        for case in 0..3 {
+            let case_test = USAGE_SUITE.enter(&["server", "client"]);
+            SERVER.get_or_setup(Some(&USAGE_SUITE), || panic!("The server must not be set up twice"));
+            CLIENT.get_or_setup(Some(&USAGE_SUITE), || ("client", Box::new(FlagOnDrop(&CLIENT_TORN_DOWN))));
+            drop(case_test);
+            assert_eq!(SERVER_TORN_DOWN.load(Ordering::SeqCst), case == 2);
+            assert_eq!(CLIENT_TORN_DOWN.load(Ordering::SeqCst), case == 2);
+        }
+        assert!(!DATABASE_TORN_DOWN.load(Ordering::SeqCst));
+
+        let last_test = USAGE_SUITE.enter(&[]);
+        drop(last_test);
+        assert!(DATABASE_TORN_DOWN.load(Ordering::SeqCst));
+    }
+}
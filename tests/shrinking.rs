@@ -0,0 +1,111 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use galvanic_test::generate::{any, Strategy};
+use galvanic_test::{fixture, test, TestFixture};
+use std::process::Output;
+
+/// Runs a single ignored test of this test binary.
+fn run_ignored_test(name: &str) -> Output {
+    common::run_ignored_test_with(name, |command| {
+        command.env_remove("GALVANIC_SEED");
+    })
+}
+
+fixture!( pair(x: i32, y: i32) -> (i32, i32) {
+    params generate(n = 20, seed = 1) {
+        (any::<i32>(), any::<i32>())
+    }
+    setup(&mut self) {
+        (*self.x, *self.y)
+    }
+});
+
+fixture!( list(values: Vec<u32>) -> Vec<u32> {
+    params generate(n = 20, seed = 2) {
+        vec(0..100u32, 0..20)
+    }
+    setup(&mut self) {
+        self.values.clone()
+    }
+});
+
+fixture!( handpicked(x: i32) -> i32 {
+    params {
+        vec![5000].into_iter()
+    }
+    setup(&mut self) {
+        *self.x
+    }
+});
+
+#[test]
+fn should_shrink_to_the_minimal_failing_integers() {
+    let output = run_ignored_test("fail_for_large_numbers");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(stdout.contains("  - pair { x: 1000, y: 0 }\n    panicked: too large\n    shrunk in "), "{}", stdout);
+    assert!(stdout.contains("Set GALVANIC_SEED=1 to replay the test."), "{}", stdout);
+}
+
+test!(
+    #[ignore]
+    fail_for_large_numbers | pair | {
+        assert!(pair.val.0 < 1000, "too large");
+    }
+);
+
+#[test]
+fn should_shrink_to_the_minimal_failing_vector() {
+    let output = run_ignored_test("fail_for_long_lists");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(stdout.contains("  - list { values: [0, 0, 0] }\n    panicked: too long"), "{}", stdout);
+}
+
+test!(
+    #[ignore]
+    fail_for_long_lists | list | {
+        assert!(list.val.len() < 3, "too long");
+    }
+);
+
+#[test]
+fn should_shrink_generated_cases_only() {
+    let output = run_ignored_test("fail_for_handpicked_and_generated");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(stdout.contains("handpicked { x: 5000 }, pair { x: 1000, y: 0 }"), "{}", stdout);
+}
+
+test!(
+    #[ignore]
+    fail_for_handpicked_and_generated | handpicked, pair | {
+        assert!(pair.val.0 < 1000, "too large");
+    }
+);
+
+#[test]
+fn should_propose_simpler_values_first() {
+    assert_eq!((0..10i32).shrink(&7), vec![0, 4, 6]);
+    assert_eq!((-10..-5i32).shrink(&-9), vec![-6, -8]);
+    assert_eq!((5..=10u8).shrink(&5), Vec::<u8>::new());
+    assert_eq!(any::<bool>().shrink(&true), vec![false]);
+    assert_eq!(any::<String>().shrink(&String::from("abcd"))[0], "");
+    assert_eq!(any::<Option<u8>>().shrink(&Some(2))[..2], [None, Some(0)]);
+    assert!((any::<u8>(), any::<u8>()).shrink(&(1, 1)).contains(&(0, 1)));
+}
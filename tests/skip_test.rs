@@ -0,0 +1,146 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use common::summary;
+use galvanic_test::{fixture, skip, test, test_suite, TestFixture};
+use std::process::{Command, Output};
+
+/// Runs a single test of this test binary, even if it is ignored.
+fn run_test(name: &str) -> Output {
+    Command::new(std::env::current_exe().unwrap())
+        .args(["--include-ignored", "--exact", name, "--nocapture"])
+        .output()
+        .expect("Could not run the test binary")
+}
+
+fixture!( environment(name: &'static str) -> String {
+    params {
+        vec!["PATH", "GALVANIC_SURELY_UNDEFINED_VARIABLE"].into_iter()
+    }
+    setup(&mut self) {
+        match std::env::var(self.name) {
+            Ok(value) => value,
+            Err(_) => skip!("the environment variable {} is not set", self.name),
+        }
+    }
+});
+
+test!(
+    skip_in_test_without_fixtures {
+        skip!("nothing to test");
+    }
+);
+
+test!(
+    skip_in_test_returning_result -> Result<(), String> {
+        skip!("nothing to test");
+    }
+);
+
+test!(
+    skip_cases_in_fixture_setup | environment | {
+        assert!(!environment.val.is_empty());
+    }
+);
+
+test!(
+    #[should_panic(expected = "the test continued")]
+    keep_other_panics_of_tests_without_fixtures {
+        if false {
+            skip!("never skipped");
+        }
+        panic!("the test continued");
+    }
+);
+
+test!(
+    #[skip_if(true)]
+    skip_if_condition_holds {
+        panic!("The test should have been skipped");
+    }
+);
+
+test!(
+    #[skip_if(1 + 1 == 3)]
+    #[should_panic(expected = "executed")]
+    execute_if_condition_does_not_hold {
+        panic!("executed");
+    }
+);
+
+test!(
+    #[skip_if(cfg!(not(windows)) || cfg!(windows))]
+    skip_parameterised_test_if_condition_holds | environment | {
+        panic!("The test should have been skipped");
+    }
+);
+
+test!(
+    #[ignore]
+    report_skipped_and_failed_cases | environment | {
+        panic!("{} is set", environment.params.name);
+    }
+);
+
+#[test]
+fn should_report_skipped_cases_with_reason() {
+    let output = run_test("report_skipped_and_failed_cases");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let expected = "Skipped the following parameterisation of the test case: the environment variable GALVANIC_SURELY_UNDEFINED_VARIABLE is not set";
+    assert!(stdout.contains(expected), "Missing {:?} in output: {}", expected, stdout);
+    assert_eq!(
+        summary(&stderr),
+        [
+            "Some parameterised test cases failed",
+            "0 of 2 parameterised test cases passed, 1 failed, 1 skipped:",
+            "  - environment { name: \"PATH\" }",
+            "    panicked: PATH is set",
+            "  - environment { name: \"GALVANIC_SURELY_UNDEFINED_VARIABLE\" }",
+            "    skipped: the environment variable GALVANIC_SURELY_UNDEFINED_VARIABLE is not set",
+        ]
+    );
+}
+
+#[test]
+fn should_print_reason_of_skipped_test() {
+    let output = run_test("skip_if_condition_holds");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success());
+    assert!(stdout.contains("The test was skipped: `true` holds"), "{}", stdout);
+}
+
+test_suite! {
+    name skip_in_suite;
+    use galvanic_test::skip;
+
+    fixture unavailable() -> u32 {
+        setup(&mut self) {
+            skip!("the resource is not available")
+        }
+    }
+
+    #[skip_if(std::env::var("PATH").is_ok())]
+    test skip_suite_test_if_condition_holds() {
+        panic!("The test should have been skipped");
+    }
+
+    test skip_suite_test_with_unavailable_fixture(unavailable) {
+        panic!("The test should have been skipped, not {}", unavailable.val);
+    }
+}
@@ -0,0 +1,195 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use galvanic_test::{assert_snapshot, fixture, test, TestFixture};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::time::Duration;
+
+const UPDATE_VAR: &str = "GALVANIC_UPDATE_SNAPSHOTS";
+
+/// Runs a single ignored test of this test binary, optionally updating its snapshots and showing its output.
+fn run_ignored_test(name: &str, update: bool) -> Output {
+    common::run_ignored_test_with(name, |command| {
+        if update {
+            command.arg("--nocapture").env(UPDATE_VAR, "1");
+        } else {
+            command.env_remove(UPDATE_VAR);
+        }
+    })
+}
+
+fn snapshot_dir(test: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots/snapshot_test").join(test)
+}
+
+fixture!( corpus_file(path: PathBuf) -> String {
+    params glob("tests/data/corpus/*.input")
+    setup(&mut self) {
+        fs::read_to_string(self.path).unwrap()
+    }
+});
+
+test!( snapshot_each_corpus_file | corpus_file | {
+    assert_snapshot!(corpus_file.val.to_uppercase());
+});
+
+test!(
+    #[parallel]
+    snapshot_each_corpus_file_in_parallel | corpus_file | {
+        assert_snapshot!(corpus_file.val.len());
+    }
+);
+
+test!( number_the_snapshots_of_a_test_case {
+    assert_snapshot!("first");
+    assert_snapshot!(format!("second {}", 2));
+});
+
+test!(
+    #[timeout(Duration::from_secs(10))]
+    snapshot_in_a_supervised_test {
+        assert_snapshot!("supervised");
+    }
+);
+
+#[test]
+#[should_panic(expected = "Snapshots can only be asserted in tests defined by `test!` or `test_suite!`.")]
+fn fail_outside_of_galvanic_tests() {
+    assert_snapshot!("unknown test");
+}
+
+#[test]
+fn should_show_the_changed_lines_of_a_mismatching_snapshot() {
+    let output = run_ignored_test("mismatch_snapshot", false);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(
+        stdout.contains(
+            "The snapshot `tests/snapshots/snapshot_test/mismatch_snapshot/snapshot.snap` \
+             does not match the output of the test (- snapshot, + output):\n  one\n- 2\n+ two\n  three\n  four\n  five\n  ...\n  seven\n  eight\n  nine\n- ten\n+ 10\n"
+        ),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains(&format!("Set {}=1 to update it.", UPDATE_VAR)), "{}", stdout);
+}
+
+test!(
+    #[ignore]
+    mismatch_snapshot {
+        assert_snapshot!("one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\n10\n");
+    }
+);
+
+#[test]
+fn should_fail_for_a_missing_snapshot() {
+    let output = run_ignored_test("miss_snapshot", false);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(
+        stdout.contains(
+            "The snapshot `tests/snapshots/snapshot_test/miss_snapshot/snapshot.snap` does not exist. \
+             The test produced:\nnew output\n"
+        ),
+        "{}",
+        stdout
+    );
+    assert!(!snapshot_dir("miss_snapshot").exists());
+}
+
+test!(
+    #[ignore]
+    miss_snapshot {
+        assert_snapshot!("new output");
+    }
+);
+
+#[test]
+fn should_detect_orphaned_snapshots() {
+    let output = run_ignored_test("orphan_snapshot", false);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(
+        stdout.contains(
+            "The following snapshots are not produced by any parameterisation of the test:\n  \
+             - tests/snapshots/snapshot_test/orphan_snapshot/tests_data_corpus_removed.input.snap\n"
+        ),
+        "{}",
+        stdout
+    );
+}
+
+test!(
+    #[ignore]
+    orphan_snapshot | corpus_file | {
+        assert_snapshot!(corpus_file.val.len());
+    }
+);
+
+#[test]
+fn should_write_snapshots_and_remove_orphans_when_updating() {
+    let directory = snapshot_dir("update_snapshots");
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(directory.join("tests_data_corpus_removed.input.snap"), "orphan").unwrap();
+    fs::write(directory.join("tests_data_corpus_numbers.input.snap"), "outdated").unwrap();
+
+    let updated = run_ignored_test("update_snapshots", true);
+    let checked = run_ignored_test("update_snapshots", false);
+    let mut snapshots: Vec<_> = fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    snapshots.sort();
+    let numbers = fs::read_to_string(directory.join("tests_data_corpus_numbers.input.snap"));
+    fs::remove_dir_all(&directory).unwrap();
+
+    let stdout = String::from_utf8_lossy(&updated.stdout);
+    assert!(updated.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("Removed the orphaned snapshot `tests/snapshots/snapshot_test/update_snapshots/tests_data_corpus_removed.input.snap`."),
+        "{}",
+        stdout
+    );
+    assert!(checked.status.success(), "{}", String::from_utf8_lossy(&checked.stdout));
+    assert_eq!(
+        snapshots,
+        vec![
+            "tests_data_corpus_empty.input.snap",
+            "tests_data_corpus_greeting.input.snap",
+            "tests_data_corpus_numbers.input.snap"
+        ]
+    );
+    assert_eq!(numbers.unwrap(), "1 2 3\n");
+}
+
+test!(
+    #[ignore]
+    update_snapshots | corpus_file | {
+        assert_snapshot!(corpus_file.val);
+    }
+);
+
+#[test]
+fn should_not_detect_orphans_if_cases_are_filtered() {
+    let output = common::run_ignored_test_with("orphan_snapshot", |command| {
+        command.env_remove(UPDATE_VAR).env("GALVANIC_CASE", "greeting");
+    });
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stdout));
+}
@@ -0,0 +1,91 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod common;
+
+use common::{run_ignored_test, summary};
+use galvanic_test::{fixture, test, TestFixture};
+
+fixture!( port(number: u32) -> u32 {
+    params {
+        vec![80, 0, 8080, 70000].into_iter()
+    }
+    setup(&mut self) {
+        *self.number
+    }
+});
+
+fixture!( address(host: &'static str) -> Result<&'static str, String> {
+    params {
+        vec!["localhost", "unknown.invalid"].into_iter()
+    }
+    setup(&mut self) {
+        match *self.host {
+            "localhost" => Ok("127.0.0.1"),
+            host => Err(format!("{} cannot be resolved", host)),
+        }
+    }
+});
+
+test!(
+    #[ignore]
+    fail_for_invalid_ports | port | {
+        assert!((1..=65535).contains(&port.val), "{} is not a valid port", port.val);
+    }
+);
+
+test!(
+    #[ignore]
+    fail_to_connect | port(80), address | -> Result<(), String> {
+        Err(format!("{}:{} refused the connection", address.val, port.val))
+    }
+);
+
+#[test]
+fn should_summarise_failed_cases_in_panic_message() {
+    let output = run_ignored_test("fail_for_invalid_ports");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        summary(&stdout),
+        [
+            "Some parameterised test cases failed",
+            "2 of 4 parameterised test cases passed, 2 failed:",
+            "  - port { number: 0 }",
+            "    panicked: 0 is not a valid port",
+            "  - port { number: 70000 }",
+            "    panicked: 70000 is not a valid port",
+        ]
+    );
+}
+
+#[test]
+fn should_summarise_returned_errors_and_fixture_errors() {
+    let output = run_ignored_test("fail_to_connect");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        summary(&stdout),
+        [
+            "Some parameterised test cases failed and some fixtures could not be set up",
+            "0 of 2 parameterised test cases passed, 1 failed, 1 could not be set up:",
+            "  - port { number: 80 }, address { host: \"localhost\" }",
+            "    returned the error \"127.0.0.1:80 refused the connection\"",
+            "  - port { number: 80 }, address { host: \"unknown.invalid\" }",
+            "    The fixture `address` could not be set up with the parameterisation address { host: \"unknown.invalid\" }: \
+             \"unknown.invalid cannot be resolved\"",
+        ]
+    );
+}
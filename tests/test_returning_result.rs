@@ -0,0 +1,118 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod basic {
+    use galvanic_test::{fixture, test, TestFixture};
+    use std::num::ParseIntError;
+
+    fixture!( number_text(text: &'static str) -> &'static str {
+        params {
+            vec!["1", "22", "333"].into_iter()
+        }
+        setup(&mut self) {
+            self.text
+        }
+    });
+
+    test!(
+        return_ok_from_test_without_fixtures -> Result<(), ParseIntError> {
+            assert_eq!("42".parse::<i32>()?, 42);
+            Ok(())
+        }
+    );
+
+    test!(
+        use_question_mark_in_parameterised_test | number_text | -> Result<(), ParseIntError> {
+            let number: usize = number_text.val.parse()?;
+            assert_eq!(number.to_string().len(), number_text.val.len());
+            Ok(())
+        }
+    );
+
+    test!(
+        #[should_panic(expected = "Some parameterised test cases failed")]
+        fail_test_case_returning_error | number_text("not a number") | -> Result<(), ParseIntError> {
+            number_text.val.parse::<usize>()?;
+            Ok(())
+        }
+    );
+}
+
+mod aggregation {
+    use galvanic_test::{fixture, test, TestFixture};
+    use std::cell::Cell;
+
+    thread_local! {
+        static EXECUTED_CASES: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fixture!( divisor(x: i32) -> i32 {
+        params {
+            vec![1, 0, 2].into_iter()
+        }
+        setup(&mut self) {
+            *self.x
+        }
+    });
+
+    test!(
+        #[should_panic(expected = "Some parameterised test cases failed")]
+        fail_for_zero_divisor | divisor | -> Result<(), String> {
+            EXECUTED_CASES.with(|cases| cases.set(cases.get() + 1));
+            42i32.checked_div(divisor.val).ok_or_else(|| String::from("division by zero"))?;
+            Ok(())
+        }
+    );
+
+    #[test]
+    fn should_execute_remaining_cases_after_an_error() {
+        assert!(std::panic::catch_unwind(fail_for_zero_divisor).is_err());
+        assert_eq!(EXECUTED_CASES.with(Cell::get), 3);
+    }
+}
+
+mod results_in_test_suite {
+    use galvanic_test::test_suite;
+
+    test_suite! {
+        use std::num::ParseIntError;
+
+        fixture port(raw: &'static str) -> &'static str {
+            params {
+                vec!["80", "8080"].into_iter()
+            }
+            setup(&mut self) {
+                self.raw
+            }
+        }
+
+        test return_ok_from_test_without_fixtures() -> Result<(), ParseIntError> {
+            assert_eq!("42".parse::<u16>()?, 42);
+            Ok(())
+        }
+
+        test return_ok_from_parameterised_test(port) -> Result<(), ParseIntError> {
+            let port: u16 = port.val.parse()?;
+            assert!(port >= 80);
+            Ok(())
+        }
+
+        #[should_panic(expected = "The test returned an error")]
+        test fail_test_without_fixtures_returning_error() -> Result<(), ParseIntError> {
+            "x".parse::<u16>()?;
+            Ok(())
+        }
+    }
+}
@@ -21,6 +21,7 @@ extern crate galvanic_mock;
 use galvanic_test::test_suite;
 
 test_suite! {
+    #[allow(clippy::assertions_on_constants)]
     test simple_test_in_unnamed_test_suite() {
         assert!(true);
     }
@@ -29,6 +30,7 @@ test_suite! {
 test_suite! {
     name named_test_suite0;
 
+    #[allow(clippy::assertions_on_constants)]
     test simple_test() {
         assert!(true);
     }
@@ -0,0 +1,158 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use galvanic_test::{fixture, test, test_suite, TestFixture};
+use std::thread;
+use std::time::Duration;
+
+fixture!( sleeper(millis: u64) -> u64 {
+    params {
+        vec![0, 10_000].into_iter()
+    }
+    setup(&mut self) {
+        *self.millis
+    }
+});
+
+fixture!( hanging_first(millis: u64) -> u64 {
+    params {
+        vec![10_000, 0, 1].into_iter()
+    }
+    setup(&mut self) {
+        *self.millis
+    }
+});
+
+fixture!( slow_setup(millis: u64) -> u64 {
+    timeout(Duration::from_millis(100))
+    params {
+        vec![0, 10_000].into_iter()
+    }
+    setup(&mut self) {
+        thread::sleep(Duration::from_millis(*self.millis));
+        *self.millis
+    }
+});
+
+fixture!( slow_tear_down() -> () {
+    timeout(Duration::from_millis(100))
+    setup(&mut self) {}
+    tear_down(&self) {
+        thread::sleep(Duration::from_secs(10));
+    }
+});
+
+fixture!( hanging_dependency() -> () {
+    timeout(Duration::from_millis(100))
+    setup(&mut self) {
+        thread::sleep(Duration::from_secs(10));
+    }
+});
+
+fixture!( dependent(hanging_dependency) -> () {
+    setup(&mut self) {}
+});
+
+fixture!( fast_fixture(millis: u64) -> u64 {
+    timeout(Duration::from_secs(10))
+    setup(&mut self) {
+        thread::sleep(Duration::from_millis(*self.millis));
+        *self.millis
+    }
+});
+
+test!(
+    #[timeout(Duration::from_millis(100))]
+    #[should_panic(expected = "timed out after 100ms in the test case with the parameterisation sleeper { millis: 10000 }")]
+    fail_parameterisation_exceeding_timeout | sleeper | {
+        thread::sleep(Duration::from_millis(sleeper.val));
+    }
+);
+
+test!(
+    #[should_panic(expected = "timed out after 100ms in setup of fixture `slow_setup` with params slow_setup { millis: 10000 }")]
+    fail_fixture_setup_exceeding_timeout | slow_setup | {}
+);
+
+test!(
+    #[should_panic(expected = "timed out after 100ms in tear_down of fixture `slow_tear_down` with params slow_tear_down")]
+    fail_fixture_tear_down_exceeding_timeout | slow_tear_down | {}
+);
+
+test!(
+    #[should_panic(expected = "2 of 3 parameterised test cases passed, 1 failed:\n  - hanging_first { millis: 10000 }\n    timed out after 100ms")]
+    #[timeout(Duration::from_millis(100))]
+    continue_with_other_parameterisations_after_timeout | hanging_first | {
+        thread::sleep(Duration::from_millis(hanging_first.val));
+    }
+);
+
+test!(
+    #[should_panic(expected = "timed out after 100ms in setup of fixture `hanging_dependency`")]
+    fail_dependency_setup_exceeding_timeout | dependent | {}
+);
+
+test!(
+    #[timeout(Duration::from_secs(10))]
+    pass_within_timeout | sleeper(1), fast_fixture(1) | {
+        assert_eq!(sleeper.val + fast_fixture.val, 2);
+    }
+);
+
+test!(
+    #[timeout(Duration::from_millis(100))]
+    #[should_panic(expected = "The test timed out after 100ms in the test.")]
+    fail_test_without_fixtures_exceeding_timeout {
+        thread::sleep(Duration::from_secs(10));
+    }
+);
+
+test!(
+    #[timeout(Duration::from_secs(10))]
+    #[should_panic(expected = "The test returned an error: \"failed\"")]
+    fail_test_returning_error_within_timeout -> Result<(), String> {
+        Err(String::from("failed"))
+    }
+);
+
+test!(
+    #[timeout(Duration::from_secs(10))]
+    #[should_panic(expected = "Some parameterised test cases failed")]
+    propagate_failures_of_supervised_test | sleeper(0) | {
+        panic!("failed");
+    }
+);
+
+test_suite! {
+    name timeout_in_suite;
+    use std::thread;
+    use std::time::Duration;
+
+    fixture hanging() -> () {
+        timeout(Duration::from_millis(100))
+        setup(&mut self) {
+            thread::sleep(Duration::from_secs(10));
+        }
+    }
+
+    #[should_panic(expected = "timed out after 100ms in setup of fixture `hanging`")]
+    test fail_suite_test_with_hanging_fixture(hanging) {}
+
+    #[timeout(Duration::from_millis(100))]
+    #[should_panic(expected = "The test timed out after 100ms in the test.")]
+    test fail_suite_test_exceeding_timeout() {
+        thread::sleep(Duration::from_secs(10));
+    }
+}